@@ -0,0 +1,158 @@
+use super::components::Faction;
+use super::events::*;
+use super::modifiers::{ModifierOp, Stat};
+use crate::interface::command::{
+    ArgKind, ArgSpec, CliCommand, CommandArgs, CommandSpec, CommandType, FlagSpec,
+};
+
+impl CliCommand for ShowStats {
+    fn spec() -> CommandSpec {
+        CommandSpec::new("stats", CommandType::System, "查看角色属性")
+            .target()
+            .flag(FlagSpec::switch("detail", "列出每项属性的修正来源"))
+    }
+
+    fn from_args(args: &CommandArgs) -> Self {
+        Self {
+            target: args.target(),
+            detail: args.flag("detail"),
+        }
+    }
+}
+
+impl CliCommand for AddModifier {
+    fn spec() -> CommandSpec {
+        CommandSpec::new("modifier", CommandType::Game, "添加属性修正 (调试用)")
+            .target()
+            .arg(ArgSpec::required("stat", ArgKind::Choice(Stat::all_names()), "属性"))
+            .arg(ArgSpec::required(
+                "op",
+                ArgKind::Choice(ModifierOp::all_names()),
+                "加值 / 百分比 / 倍率(%) / 覆盖 / 下限 / 上限",
+            ))
+            .arg(ArgSpec::required("value", ArgKind::Int, "数值"))
+            .arg(ArgSpec::required("source", ArgKind::Word, "来源 buff:<名字> 或 aura:<名字>"))
+    }
+
+    fn from_args(args: &CommandArgs) -> Self {
+        let op: String = args.get("op");
+        Self {
+            target: args.target(),
+            stat: args.get("stat"),
+            op: ModifierOp::parse(&op, args.get("value"))
+                .expect("op is validated by ArgKind::Choice"),
+            source: args.get("source"),
+        }
+    }
+}
+
+impl CliCommand for RemoveModifier {
+    fn spec() -> CommandSpec {
+        CommandSpec::new("unmodifier", CommandType::Game, "移除某个来源的属性修正 (调试用)")
+            .target()
+            .arg(ArgSpec::required("source", ArgKind::Word, "来源 buff:<名字> 或 aura:<名字>"))
+    }
+
+    fn from_args(args: &CommandArgs) -> Self {
+        Self {
+            target: args.target(),
+            source: args.get("source"),
+        }
+    }
+}
+
+impl CliCommand for GainExp {
+    fn spec() -> CommandSpec {
+        CommandSpec::new("gain_exp", CommandType::Game, "获得经验 (调试用)")
+            .target()
+            .arg(ArgSpec::required("amount", ArgKind::UInt, "经验值"))
+    }
+
+    fn from_args(args: &CommandArgs) -> Self {
+        Self {
+            target: args.target(),
+            amount: non_negative(args.get("amount")),
+        }
+    }
+}
+
+impl CliCommand for TakeDamage {
+    fn spec() -> CommandSpec {
+        CommandSpec::new("take_damage", CommandType::Game, "受到伤害 (调试用)")
+            .target()
+            .arg(ArgSpec::required("damage", ArgKind::UInt, "伤害值"))
+    }
+
+    fn from_args(args: &CommandArgs) -> Self {
+        Self {
+            target: args.target(),
+            damage: non_negative(args.get("damage")),
+        }
+    }
+}
+
+impl CliCommand for Heal {
+    fn spec() -> CommandSpec {
+        CommandSpec::new("heal", CommandType::Game, "恢复生命值 (调试用)")
+            .target()
+            .arg(ArgSpec::required("amount", ArgKind::UInt, "治疗量"))
+    }
+
+    fn from_args(args: &CommandArgs) -> Self {
+        Self {
+            target: args.target(),
+            amount: non_negative(args.get("amount")),
+        }
+    }
+}
+
+impl CliCommand for SpawnActor {
+    fn spec() -> CommandSpec {
+        CommandSpec::new("spawn", CommandType::Game, "生成角色 (调试用)")
+            .arg(ArgSpec::required(
+                "name",
+                ArgKind::Word,
+                "名字，之后可用 @name 指定",
+            ))
+            .arg(
+                ArgSpec::optional("faction", ArgKind::Choice(Faction::all_names()), "阵营")
+                    .default("monster"),
+            )
+    }
+
+    fn from_args(args: &CommandArgs) -> Self {
+        Self {
+            name: args.get("name"),
+            faction: args.get("faction"),
+        }
+    }
+}
+
+impl CliCommand for NewGame {
+    fn spec() -> CommandSpec {
+        CommandSpec::new("new_game", CommandType::System, "选择职业并开始新游戏")
+            .pre_game()
+            .arg(ArgSpec::required("class", ArgKind::Word, "职业 id（classes 查看）"))
+    }
+
+    fn from_args(args: &CommandArgs) -> Self {
+        Self {
+            class: args.get("class"),
+        }
+    }
+}
+
+impl CliCommand for ShowClasses {
+    fn spec() -> CommandSpec {
+        CommandSpec::new("classes", CommandType::System, "列出可选职业").pre_game()
+    }
+
+    fn from_args(_: &CommandArgs) -> Self {
+        Self
+    }
+}
+
+/// `UInt` 参数转成事件里的 `i32`，超出范围按 `i32::MAX` 处理
+fn non_negative(value: u32) -> i32 {
+    i32::try_from(value).unwrap_or(i32::MAX)
+}
@@ -1,6 +1,10 @@
+use crate::data::{classes::ClassDef, progression::Progression};
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
 
-/// 角色属性组件
+/// 角色属性组件：由 `BaseStats` 经修正管线推导，只由 `recalculate_stats` 写入（生命值除外）
 #[derive(Component, Debug, Clone)]
 pub struct Stats {
     pub hp: i32,
@@ -27,35 +31,11 @@ impl Default for Stats {
 }
 
 impl Stats {
-    /// 计算升级所需经验：exp_to_next = 10 × lv²
-    pub fn exp_to_next(&self) -> i32 {
-        10 * self.lv * self.lv
-    }
-
-    /// 检查是否可以升级
-    pub fn can_level_up(&self) -> bool {
-        self.exp >= self.exp_to_next()
-    }
-
-    /// 执行升级：+2 max_hp, +1 atk, +1 def
-    pub fn level_up(&mut self) {
-        if self.can_level_up() {
-            self.exp -= self.exp_to_next();
-            self.lv += 1;
-            self.max_hp += 2;
-            self.atk += 1;
-            self.def += 1;
-            // 升级时恢复满血
-            self.hp = self.max_hp;
-        }
-    }
-
-    /// 获得经验
-    pub fn gain_exp(&mut self, amount: i32) {
-        self.exp += amount;
-        // 连续升级直到无法升级
-        while self.can_level_up() {
-            self.level_up();
+    /// `经验: 5/40`；满级时为 `经验: 5/满级`
+    pub fn exp_label(&self, progression: &Progression) -> String {
+        match progression.exp_to_next(self.lv) {
+            Some(needed) => format!("{}/{needed}", self.exp),
+            None => format!("{}/满级", self.exp),
         }
     }
 
@@ -82,11 +62,64 @@ impl Stats {
     }
 }
 
+/// 升级奖励的技能点（尚未分配）
+#[derive(Component, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SkillPoints(pub u32);
+
 /// 玩家标记组件
 #[derive(Component)]
 pub struct Player;
 
-/// 基础属性（不受装备影响的原始属性）
+/// 职业（`Classes` 中的 id）：决定每级成长与可用装备，随存档保存
+#[derive(Component, Debug, Clone, PartialEq, Eq)]
+pub struct CharacterClass(pub String);
+
+/// 新游戏选择的职业，`spawn_player` 据此生成玩家
+#[derive(Resource, Debug, Clone, PartialEq, Eq)]
+pub struct ChosenClass(pub String);
+
+/// 阵营
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Faction {
+    Player,
+    Monster,
+    Neutral,
+}
+
+impl Faction {
+    /// 所有阵营名称（CLI 参数）
+    pub fn all_names() -> &'static [&'static str] {
+        &["player", "monster", "neutral"]
+    }
+}
+
+impl FromStr for Faction {
+    type Err = String;
+
+    fn from_str(raw: &str) -> Result<Self, Self::Err> {
+        match raw.to_lowercase().as_str() {
+            "player" => Ok(Faction::Player),
+            "monster" => Ok(Faction::Monster),
+            "neutral" => Ok(Faction::Neutral),
+            other => Err(format!("unknown faction \"{other}\"")),
+        }
+    }
+}
+
+impl fmt::Display for Faction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Faction::Player => "player",
+            Faction::Monster => "monster",
+            Faction::Neutral => "neutral",
+        };
+        f.write_str(name)
+    }
+}
+
+/// 基础属性（不受装备影响的原始属性）与等级进度：等级和经验只在这里增长，
+/// 等级成长由成长曲线换算为修正，`Stats` 随之重算
 #[derive(Component, Debug, Clone)]
 pub struct BaseStats {
     pub max_hp: i32,
@@ -110,6 +143,36 @@ impl Default for BaseStats {
     }
 }
 
+impl BaseStats {
+    /// 升级所需经验（见成长曲线）；已达等级上限时为 `None`
+    pub fn exp_to_next(&self, progression: &Progression) -> Option<i32> {
+        progression.exp_to_next(self.lv)
+    }
+
+    /// 检查是否可以升级
+    pub fn can_level_up(&self, progression: &Progression) -> bool {
+        self.exp_to_next(progression).is_some_and(|needed| self.exp >= needed)
+    }
+
+    /// 执行升级：只推进等级，属性成长在重算时按等级得出
+    pub fn level_up(&mut self, progression: &Progression) {
+        if let Some(needed) = self.exp_to_next(progression)
+            && self.exp >= needed
+        {
+            self.exp -= needed;
+            self.lv += 1;
+        }
+    }
+
+    /// 获得经验，连续升级直到无法升级；之后需要发送 `RecalculateStats`
+    pub fn gain_exp(&mut self, amount: i32, progression: &Progression) {
+        self.exp = self.exp.saturating_add(amount);
+        while self.can_level_up(progression) {
+            self.level_up(progression);
+        }
+    }
+}
+
 impl From<&Stats> for BaseStats {
     fn from(stats: &Stats) -> Self {
         Self {
@@ -122,3 +185,31 @@ impl From<&Stats> for BaseStats {
         }
     }
 }
+
+/// 职业的 1 级属性
+impl From<&ClassDef> for BaseStats {
+    fn from(class: &ClassDef) -> Self {
+        Self {
+            max_hp: class.base.max_hp,
+            atk: class.base.atk,
+            def: class.base.def,
+            rng: class.base.rng,
+            ..Self::default()
+        }
+    }
+}
+
+/// 尚未计算任何修正的属性：满血
+impl From<&BaseStats> for Stats {
+    fn from(base: &BaseStats) -> Self {
+        Self {
+            hp: base.max_hp,
+            max_hp: base.max_hp,
+            atk: base.atk,
+            def: base.def,
+            lv: base.lv,
+            exp: base.exp,
+            rng: base.rng,
+        }
+    }
+}
@@ -1,5 +1,9 @@
 use bevy::prelude::*;
 
+use super::components::Faction;
+use super::modifiers::{ModifierOp, Stat};
+use super::target::Target;
+
 /// 装备变更事件 - 触发属性重新计算
 #[derive(Event)]
 pub struct EquipmentChanged {
@@ -9,14 +13,14 @@ pub struct EquipmentChanged {
 /// 获得经验事件
 #[derive(Event)]
 pub struct GainExp {
-    pub entity: Entity,
+    pub target: Target,
     pub amount: i32,
 }
 
 /// 受到伤害事件
 #[derive(Event)]
 pub struct TakeDamage {
-    pub entity: Entity,
+    pub target: Target,
     pub damage: i32,
 }
 
@@ -36,7 +40,7 @@ pub struct LevelUp {
 /// 治疗事件
 #[derive(Event)]
 pub struct Heal {
-    pub entity: Entity,
+    pub target: Target,
     pub amount: i32,
 }
 
@@ -49,5 +53,41 @@ pub struct RecalculateStats {
 /// 显示属性事件（用于命令行）
 #[derive(Event)]
 pub struct ShowStats {
-    pub entity: Option<Entity>,  // None 表示显示玩家属性
+    pub target: Target,
+    /// 逐项列出每个属性的修正来源
+    pub detail: bool,
+}
+
+/// 添加一条增益 / 光环修正（调试用）
+#[derive(Event)]
+pub struct AddModifier {
+    pub target: Target,
+    pub stat: Stat,
+    pub op: ModifierOp,
+    /// 来源标签 `buff:<name>` / `aura:<name>`，在处理时校验
+    pub source: String,
+}
+
+/// 移除某个来源的全部修正（调试用）
+#[derive(Event)]
+pub struct RemoveModifier {
+    pub target: Target,
+    pub source: String,
 }
+
+/// 生成一个非玩家角色（调试用）
+#[derive(Event)]
+pub struct SpawnActor {
+    pub name: String,
+    pub faction: Faction,
+}
+
+/// 以某个职业开始新游戏（职业选择阶段）
+#[derive(Event)]
+pub struct NewGame {
+    pub class: String,
+}
+
+/// 列出可选职业
+#[derive(Event)]
+pub struct ShowClasses;
@@ -1,8 +1,12 @@
+pub mod commands;
 pub mod components;
 pub mod events;
+pub mod modifiers;
 pub mod systems;
+pub mod target;
 
-use crate::core::states::AppState;
+use crate::core::states::{AppState, accepts_input};
+use crate::interface::command::CliAppExt;
 use bevy::prelude::*;
 use events::*;
 use systems::*;
@@ -14,14 +18,26 @@ impl Plugin for CharacterPlugin {
         app
             // 注册事件
             .add_event::<EquipmentChanged>()
-            .add_event::<GainExp>()
-            .add_event::<TakeDamage>()
             .add_event::<Death>()
             .add_event::<LevelUp>()
-            .add_event::<Heal>()
             .add_event::<RecalculateStats>()
-            .add_event::<ShowStats>()
-            // 在游戏开始时生成玩家
+            // 事件 + CLI 命令：stats / gain_exp / take_damage / heal / spawn / modifier / unmodifier
+            .register_cli_command::<ShowStats>()
+            .register_cli_command::<GainExp>()
+            .register_cli_command::<TakeDamage>()
+            .register_cli_command::<Heal>()
+            .register_cli_command::<SpawnActor>()
+            .register_cli_command::<AddModifier>()
+            .register_cli_command::<RemoveModifier>()
+            // 职业选择：new_game / classes
+            .register_cli_command::<NewGame>()
+            .register_cli_command::<ShowClasses>()
+            .add_systems(OnEnter(AppState::CharacterSelect), enter_character_select)
+            .add_systems(
+                Update,
+                (start_new_game, show_classes).run_if(accepts_input),
+            )
+            // 在游戏开始时按所选职业生成玩家
             .add_systems(OnEnter(AppState::InGame), spawn_player)
             // 游戏中的系统
             .add_systems(
@@ -31,8 +47,12 @@ impl Plugin for CharacterPlugin {
                     handle_take_damage,
                     handle_heal,
                     handle_equipment_changed,
+                    handle_progression_reloaded,
+                    handle_add_modifier,
+                    handle_remove_modifier,
                     recalculate_stats,
                     show_stats,
+                    spawn_actor,
                 )
                     .run_if(in_state(AppState::InGame)),
             );
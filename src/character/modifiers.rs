@@ -0,0 +1,389 @@
+//! 属性修正管线：等级、装备、增益、状态效果、光环都以 [`StatModifier`] 的形式作用于基础属性
+//!
+//! 求值顺序（同一属性内）：
+//!
+//! ```text
+//! 1. 基础值 + 所有 Flat                    （固定加成）
+//! 2. × (100% + 所有 PercentAdd 之和)       （百分比加成先相加再乘）
+//! 3. × 每个 Multiply                      （乘区之间连乘）
+//! 4. Override                             （多个时最后收集到的生效）
+//! 5. Min / Max 夹取                        （取最严格的下限 / 上限）
+//! 6. 四舍五入为整数
+//! ```
+//!
+//! 收集顺序：等级 → 装备（head/body/weapon/accessory）→ [`StatModifiers`] 组件内的顺序
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+use super::components::{BaseStats, Stats};
+use crate::data::{progression::Progression, registry::ItemRegistry};
+use crate::equipment::components::Equipment;
+
+/// 受修正影响的属性
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Stat {
+    MaxHp,
+    Atk,
+    Def,
+    Rng,
+}
+
+impl Stat {
+    pub const ALL: [Stat; 4] = [Stat::MaxHp, Stat::Atk, Stat::Def, Stat::Rng];
+
+    /// 所有属性名称（CLI 参数）
+    pub fn all_names() -> &'static [&'static str] {
+        &["max_hp", "atk", "def", "rng"]
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Stat::MaxHp => "max_hp",
+            Stat::Atk => "atk",
+            Stat::Def => "def",
+            Stat::Rng => "rng",
+        }
+    }
+
+    /// 显示名称
+    pub fn label(self) -> &'static str {
+        match self {
+            Stat::MaxHp => "生命上限",
+            Stat::Atk => "攻击力",
+            Stat::Def => "防御力",
+            Stat::Rng => "攻击距离",
+        }
+    }
+
+    pub fn base(self, base: &BaseStats) -> i32 {
+        match self {
+            Stat::MaxHp => base.max_hp,
+            Stat::Atk => base.atk,
+            Stat::Def => base.def,
+            Stat::Rng => base.rng,
+        }
+    }
+
+    pub fn get(self, stats: &Stats) -> i32 {
+        match self {
+            Stat::MaxHp => stats.max_hp,
+            Stat::Atk => stats.atk,
+            Stat::Def => stats.def,
+            Stat::Rng => stats.rng,
+        }
+    }
+
+    pub fn set(self, stats: &mut Stats, value: i32) {
+        match self {
+            Stat::MaxHp => stats.max_hp = value,
+            Stat::Atk => stats.atk = value,
+            Stat::Def => stats.def = value,
+            Stat::Rng => stats.rng = value,
+        }
+    }
+}
+
+impl FromStr for Stat {
+    type Err = String;
+
+    fn from_str(raw: &str) -> Result<Self, Self::Err> {
+        Stat::ALL
+            .into_iter()
+            .find(|stat| stat.as_str() == raw)
+            .ok_or_else(|| format!("unknown stat \"{raw}\""))
+    }
+}
+
+/// 修正方式；百分比与倍率都以百分数表示（`Multiply(150)` = ×1.5）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ModifierOp {
+    Flat(i32),
+    PercentAdd(i32),
+    Multiply(i32),
+    Override(i32),
+    Min(i32),
+    Max(i32),
+}
+
+impl ModifierOp {
+    /// CLI 中的写法：add / pct / mul / set / min / max
+    pub fn all_names() -> &'static [&'static str] {
+        &["add", "pct", "mul", "set", "min", "max"]
+    }
+
+    pub fn parse(name: &str, value: i32) -> Option<Self> {
+        Some(match name {
+            "add" => ModifierOp::Flat(value),
+            "pct" => ModifierOp::PercentAdd(value),
+            "mul" => ModifierOp::Multiply(value),
+            "set" => ModifierOp::Override(value),
+            "min" => ModifierOp::Min(value),
+            "max" => ModifierOp::Max(value),
+            _ => return None,
+        })
+    }
+
+    /// 叠加 `stacks` 层：加值与百分比按层数放大，其余不变
+    pub fn scaled(self, stacks: u32) -> Self {
+        let stacks = stacks as i32;
+        match self {
+            ModifierOp::Flat(v) => ModifierOp::Flat(v * stacks),
+            ModifierOp::PercentAdd(v) => ModifierOp::PercentAdd(v * stacks),
+            other => other,
+        }
+    }
+
+    /// 求值阶段，见模块文档
+    fn stage(self) -> u8 {
+        match self {
+            ModifierOp::Flat(_) => 1,
+            ModifierOp::PercentAdd(_) => 2,
+            ModifierOp::Multiply(_) => 3,
+            ModifierOp::Override(_) => 4,
+            ModifierOp::Min(_) | ModifierOp::Max(_) => 5,
+        }
+    }
+}
+
+impl fmt::Display for ModifierOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ModifierOp::Flat(v) => write!(f, "{v:+}"),
+            ModifierOp::PercentAdd(v) => write!(f, "{v:+}%"),
+            ModifierOp::Multiply(v) => write!(f, "×{v}%"),
+            ModifierOp::Override(v) => write!(f, "={v}"),
+            ModifierOp::Min(v) => write!(f, "≥{v}"),
+            ModifierOp::Max(v) => write!(f, "≤{v}"),
+        }
+    }
+}
+
+/// 修正来源；`Display` 输出标签（`level` / `equipment:weapon` / `buff:<name>` / `effect:<id>` / `aura:<name>`）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ModifierSource {
+    /// 等级成长，附带当前等级
+    Level(i32),
+    /// 装备槽与物品名
+    Equipment { slot: &'static str, item: String },
+    /// 增益 / 减益
+    Buff(String),
+    /// 状态效果（由 `status` 模块维护）
+    Effect { id: String, name: String },
+    /// 来自其他角色的光环
+    Aura(String),
+}
+
+impl ModifierSource {
+    /// 显示名称
+    pub fn label(&self) -> String {
+        match self {
+            ModifierSource::Level(lv) => format!("等级 Lv{lv}"),
+            ModifierSource::Equipment { slot, item } => format!("装备 {slot} {item}"),
+            ModifierSource::Buff(name) => format!("增益 {name}"),
+            ModifierSource::Effect { name, .. } => format!("效果 {name}"),
+            ModifierSource::Aura(name) => format!("光环 {name}"),
+        }
+    }
+}
+
+impl fmt::Display for ModifierSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ModifierSource::Level(_) => f.write_str("level"),
+            ModifierSource::Equipment { slot, .. } => write!(f, "equipment:{slot}"),
+            ModifierSource::Buff(name) => write!(f, "buff:{name}"),
+            ModifierSource::Effect { id, .. } => write!(f, "effect:{id}"),
+            ModifierSource::Aura(name) => write!(f, "aura:{name}"),
+        }
+    }
+}
+
+/// 只能解析外部可添加的来源：`buff:<name>` / `aura:<name>`
+impl FromStr for ModifierSource {
+    type Err = String;
+
+    fn from_str(raw: &str) -> Result<Self, Self::Err> {
+        match raw.split_once(':') {
+            Some(("buff", name)) if !name.is_empty() => Ok(ModifierSource::Buff(name.into())),
+            Some(("aura", name)) if !name.is_empty() => Ok(ModifierSource::Aura(name.into())),
+            _ => Err(format!("来源应为 buff:<名字> 或 aura:<名字>，得到 \"{raw}\"")),
+        }
+    }
+}
+
+/// 一条属性修正
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatModifier {
+    pub stat: Stat,
+    pub op: ModifierOp,
+    pub source: ModifierSource,
+}
+
+impl StatModifier {
+    pub fn new(stat: Stat, op: ModifierOp, source: ModifierSource) -> Self {
+        Self { stat, op, source }
+    }
+}
+
+/// 等级与装备之外的修正（增益、光环、状态效果），由对应系统写入后发送 `RecalculateStats`；
+/// 增益与光环随存档保存，效果的修正读档时由效果重新生成
+#[derive(Component, Debug, Clone, Default)]
+pub struct StatModifiers(pub Vec<StatModifier>);
+
+impl StatModifiers {
+    pub fn add(&mut self, modifier: StatModifier) {
+        self.0.push(modifier);
+    }
+
+    /// 移除来源标签为 `tag` 的修正，返回移除数量
+    pub fn remove_source(&mut self, tag: &str) -> usize {
+        let before = self.0.len();
+        self.0.retain(|modifier| modifier.source.to_string() != tag);
+        before - self.0.len()
+    }
+}
+
+/// 等级成长：成长曲线从 1 级到 lv 级的累计成长
+pub fn level_modifiers(lv: i32, progression: &Progression) -> Vec<StatModifier> {
+    let growth = progression.total_growth(lv);
+    [
+        (Stat::MaxHp, growth.max_hp),
+        (Stat::Atk, growth.atk),
+        (Stat::Def, growth.def),
+        (Stat::Rng, growth.rng),
+    ]
+    .into_iter()
+    .filter(|(_, value)| *value != 0)
+    .map(|(stat, value)| {
+        StatModifier::new(stat, ModifierOp::Flat(value), ModifierSource::Level(lv))
+    })
+    .collect()
+}
+
+/// 装备的属性加成（物品数据里的 max_hp / atk / def / rng 均为固定加成）
+pub fn equipment_modifiers(equipment: &Equipment, registry: &ItemRegistry) -> Vec<StatModifier> {
+    let mut out = Vec::new();
+    for &slot in Equipment::all_slots() {
+        let Some(stack) = equipment.get_slot(slot) else {
+            continue;
+        };
+        let item = registry.get(stack.item);
+        for (stat, value) in [
+            (Stat::MaxHp, item.max_hp),
+            (Stat::Atk, item.atk),
+            (Stat::Def, item.def),
+            (Stat::Rng, item.rng),
+        ] {
+            if value != 0 {
+                out.push(StatModifier::new(
+                    stat,
+                    ModifierOp::Flat(value),
+                    ModifierSource::Equipment {
+                        slot,
+                        item: item.name.clone(),
+                    },
+                ));
+            }
+        }
+    }
+    out
+}
+
+/// 按收集顺序汇总一个角色的全部修正
+pub fn collect_modifiers(
+    lv: i32,
+    equipment: Option<&Equipment>,
+    extra: Option<&StatModifiers>,
+    registry: &ItemRegistry,
+    progression: &Progression,
+) -> Vec<StatModifier> {
+    let mut out = level_modifiers(lv, progression);
+    if let Some(equipment) = equipment {
+        out.extend(equipment_modifiers(equipment, registry));
+    }
+    if let Some(extra) = extra {
+        out.extend(extra.0.iter().cloned());
+    }
+    out
+}
+
+/// 一个属性的求值过程：每条修正实际带来的变化量
+#[derive(Debug, Clone, PartialEq)]
+pub struct Breakdown {
+    pub stat: Stat,
+    pub base: i32,
+    /// 按求值顺序排列；变化量未取整
+    pub steps: Vec<(StatModifier, f64)>,
+    pub value: i32,
+}
+
+impl Breakdown {
+    /// `攻击力 15 = 基础 2` 加每条修正一行
+    pub fn describe(&self) -> String {
+        let mut out = format!("{} {} = 基础 {}", self.stat.label(), self.value, self.base);
+        for (modifier, delta) in &self.steps {
+            out.push_str(&format!(
+                "\n  {:<6} {}（{}）",
+                modifier.op.to_string(),
+                modifier.source.label(),
+                format_delta(*delta)
+            ));
+        }
+        out
+    }
+}
+
+fn format_delta(delta: f64) -> String {
+    if delta.fract() == 0.0 {
+        format!("{delta:+}")
+    } else {
+        format!("{delta:+.1}")
+    }
+}
+
+/// 按模块文档的顺序计算 `stat` 的最终值
+pub fn evaluate(stat: Stat, base: i32, modifiers: &[StatModifier]) -> Breakdown {
+    let mut relevant: Vec<&StatModifier> =
+        modifiers.iter().filter(|m| m.stat == stat).collect();
+    // 稳定排序：同一阶段内保持收集顺序
+    relevant.sort_by_key(|m| m.op.stage());
+
+    let mut value = base as f64;
+    // 百分比加成都以 Flat 之后的值为底
+    let mut percent_base = None;
+    let mut lower = f64::NEG_INFINITY;
+    let mut upper = f64::INFINITY;
+
+    let mut steps = Vec::new();
+    for &modifier in &relevant {
+        let before = value;
+        match modifier.op {
+            ModifierOp::Flat(v) => value += v as f64,
+            ModifierOp::PercentAdd(v) => {
+                let base = *percent_base.get_or_insert(value);
+                value += base * v as f64 / 100.0;
+            }
+            ModifierOp::Multiply(v) => value *= v as f64 / 100.0,
+            ModifierOp::Override(v) => value = v as f64,
+            ModifierOp::Min(v) => {
+                lower = lower.max(v as f64);
+                value = value.max(lower).min(upper);
+            }
+            ModifierOp::Max(v) => {
+                upper = upper.min(v as f64);
+                value = value.max(lower).min(upper);
+            }
+        }
+        steps.push((modifier.clone(), value - before));
+    }
+
+    Breakdown {
+        stat,
+        base,
+        steps,
+        value: value.round() as i32,
+    }
+}
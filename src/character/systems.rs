@@ -1,43 +1,81 @@
-use super::{components::*, events::*};
-use crate::core::events::LogEvent;
+use super::{components::*, events::*, modifiers::*, target::{Target, Targets}};
+use crate::core::{args::LaunchArgs, events::LogEvent, states::AppState};
+use crate::data::classes::{ClassDef, Classes};
+use crate::data::events::{ClassesReloaded, ProgressionReloaded};
+use crate::data::{progression::Progression, registry::ItemRegistry};
 use crate::equipment::components::Equipment;
+use crate::inventory::components::{Backpack, ItemStack};
+use crate::inventory::events::GiveItemEvent;
+use crate::status::components::StatusEffects;
 use bevy::prelude::*;
 
-/// 处理获得经验事件
+/// 处理获得经验事件：按成长曲线推进 `BaseStats` 的等级，发放途经各等级的奖励并重算属性
+#[allow(clippy::too_many_arguments)]
 pub fn handle_gain_exp(
     mut ev_gain_exp: EventReader<GainExp>,
     mut ev_level_up: EventWriter<LevelUp>,
-    _ev_log: EventWriter<LogEvent>,
-    mut player_query: Query<(Entity, &mut Stats), With<Player>>,
+    mut ev_recalculate: EventWriter<RecalculateStats>,
+    mut ev_give: EventWriter<GiveItemEvent>,
+    targets: Targets,
+    mut stats_query: Query<(&mut BaseStats, Option<&mut SkillPoints>, Option<&CharacterClass>)>,
+    progression: Res<Progression>,
+    classes: Res<Classes>,
+    registry: Res<ItemRegistry>,
 ) {
     for ev in ev_gain_exp.read() {
-        // 如果是 PLACEHOLDER，查找玩家实体
-        let target_entity = if ev.entity == Entity::PLACEHOLDER {
-            if let Ok((player_entity, _)) = player_query.single() {
-                player_entity
-            } else {
-                continue;
-            }
-        } else {
-            ev.entity
-        };
+        let resolved = targets.resolve(&ev.target);
+        if resolved.is_empty() {
+            crate::interface::debug_cli::queue_game_log(format!("未找到目标 {}", ev.target));
+        }
 
-        if let Ok((_, mut stats)) = player_query.get_mut(target_entity) {
-            let old_level = stats.lv;
-            stats.gain_exp(ev.amount);
+        for entity in resolved {
+            let Ok((mut base_stats, mut skill_points, class)) = stats_query.get_mut(entity) else {
+                continue;
+            };
+            let progression = classes.progression_for(class.map(|c| c.0.as_str()), &progression);
+            let label = targets.label(entity);
+            let old_level = base_stats.lv;
+            base_stats.gain_exp(ev.amount, &progression);
+            let new_level = base_stats.lv;
+            // 经验也显示在 Stats 上
+            ev_recalculate.write(RecalculateStats { entity });
 
             // 使用游戏日志
-            crate::interface::debug_cli::queue_game_log(format!("获得 {} 经验", ev.amount));
+            crate::interface::debug_cli::queue_game_log(format!("{label}获得 {} 经验", ev.amount));
 
-            if stats.lv > old_level {
-                ev_level_up.write(LevelUp {
-                    entity: ev.entity,
-                    new_level: stats.lv,
-                });
+            if new_level > old_level {
+                ev_level_up.write(LevelUp { entity, new_level });
+                let growth = progression.growth_between(old_level, new_level);
                 crate::interface::debug_cli::queue_game_log(format!(
-                    "升级！等级 {} → {}，生命值 +2，攻击力 +1，防御力 +1",
-                    old_level, stats.lv
+                    "{label}升级！等级 {old_level} → {new_level}，{}",
+                    growth.describe()
                 ));
+
+                for reward in (old_level + 1..=new_level).filter_map(|lv| progression.reward(lv)) {
+                    for (id, count) in &reward.items {
+                        let name = registry.by_id(id).map_or(id.as_str(), |item| {
+                            registry.get(item).name.as_str()
+                        });
+                        crate::interface::debug_cli::queue_game_log(format!(
+                            "{label}获得 Lv{} 奖励 {name} ×{count}",
+                            reward.level
+                        ));
+                        ev_give.write(GiveItemEvent {
+                            target: Target::Entity(entity),
+                            id: id.clone(),
+                            count: *count,
+                        });
+                    }
+                    if reward.skill_points > 0
+                        && let Some(points) = skill_points.as_mut()
+                    {
+                        points.0 += reward.skill_points;
+                        crate::interface::debug_cli::queue_game_log(format!(
+                            "{label}获得 {} 技能点（共 {}）",
+                            reward.skill_points, points.0
+                        ));
+                    }
+                }
             }
         }
     }
@@ -47,34 +85,30 @@ pub fn handle_gain_exp(
 pub fn handle_take_damage(
     mut ev_take_damage: EventReader<TakeDamage>,
     mut ev_death: EventWriter<Death>,
-    _ev_log: EventWriter<LogEvent>,
-    mut player_query: Query<(Entity, &mut Stats), With<Player>>,
+    targets: Targets,
+    mut stats_query: Query<&mut Stats>,
 ) {
     for ev in ev_take_damage.read() {
-        // 如果是 PLACEHOLDER，查找玩家实体
-        let target_entity = if ev.entity == Entity::PLACEHOLDER {
-            if let Ok((player_entity, _)) = player_query.single() {
-                player_entity
-            } else {
-                continue;
-            }
-        } else {
-            ev.entity
-        };
+        let resolved = targets.resolve(&ev.target);
+        if resolved.is_empty() {
+            crate::interface::debug_cli::queue_game_log(format!("未找到目标 {}", ev.target));
+        }
 
-        if let Ok((_, mut stats)) = player_query.get_mut(target_entity) {
+        for entity in resolved {
+            let Ok(mut stats) = stats_query.get_mut(entity) else {
+                continue;
+            };
+            let label = targets.label(entity);
             let is_dead = stats.take_damage(ev.damage);
 
             crate::interface::debug_cli::queue_game_log(format!(
-                "受到 {} 点伤害，当前生命值：{}/{}",
+                "{label}受到 {} 点伤害，当前生命值：{}/{}",
                 ev.damage, stats.hp, stats.max_hp
             ));
 
             if is_dead {
-                ev_death.write(Death {
-                    entity: target_entity,
-                });
-                crate::interface::debug_cli::queue_game_log("死亡！".to_string());
+                ev_death.write(Death { entity });
+                crate::interface::debug_cli::queue_game_log(format!("{label}死亡！"));
             }
         }
     }
@@ -83,30 +117,30 @@ pub fn handle_take_damage(
 /// 处理治疗事件
 pub fn handle_heal(
     mut ev_heal: EventReader<Heal>,
-    _ev_log: EventWriter<LogEvent>,
-    mut player_query: Query<(Entity, &mut Stats), With<Player>>,
+    targets: Targets,
+    mut stats_query: Query<&mut Stats>,
 ) {
     for ev in ev_heal.read() {
-        // 如果是 PLACEHOLDER，查找玩家实体
-        let target_entity = if ev.entity == Entity::PLACEHOLDER {
-            if let Ok((player_entity, _)) = player_query.single() {
-                player_entity
-            } else {
-                continue;
-            }
-        } else {
-            ev.entity
-        };
+        let resolved = targets.resolve(&ev.target);
+        if resolved.is_empty() {
+            crate::interface::debug_cli::queue_game_log(format!("未找到目标 {}", ev.target));
+        }
 
-        if let Ok((_, mut stats)) = player_query.get_mut(target_entity) {
+        for entity in resolved {
+            let Ok(mut stats) = stats_query.get_mut(entity) else {
+                continue;
+            };
             let old_hp = stats.hp;
             stats.heal(ev.amount);
             let healed = stats.hp - old_hp;
 
             if healed > 0 {
                 crate::interface::debug_cli::queue_game_log(format!(
-                    "恢复 {} 点生命值，当前生命值：{}/{}",
-                    healed, stats.hp, stats.max_hp
+                    "{}恢复 {} 点生命值，当前生命值：{}/{}",
+                    targets.label(entity),
+                    healed,
+                    stats.hp,
+                    stats.max_hp
                 ));
             }
         }
@@ -123,115 +157,387 @@ pub fn handle_equipment_changed(
     }
 }
 
-/// 重新计算属性（基础属性 + 装备加成）
+/// 成长曲线或职业重新加载后，所有角色按新曲线重算等级成长
+pub fn handle_progression_reloaded(
+    mut ev_progression: EventReader<ProgressionReloaded>,
+    mut ev_classes: EventReader<ClassesReloaded>,
+    mut ev_recalculate: EventWriter<RecalculateStats>,
+    actors: Query<Entity, With<Stats>>,
+) {
+    if ev_progression.read().count() + ev_classes.read().count() == 0 {
+        return;
+    }
+    for entity in &actors {
+        ev_recalculate.write(RecalculateStats { entity });
+    }
+}
+
+/// 重新计算属性需要读写的组件
+type RecalculatedStats<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static mut Stats,
+        &'static BaseStats,
+        Option<&'static Equipment>,
+        Option<&'static StatModifiers>,
+        Option<&'static CharacterClass>,
+    ),
+>;
+
+/// 重新计算属性：基础属性经修正管线（等级、装备、增益、光环）求值
 pub fn recalculate_stats(
     mut ev_recalculate: EventReader<RecalculateStats>,
-    mut stats_query: Query<(&mut Stats, &BaseStats)>,
-    equipment: Res<Equipment>,
+    mut stats_query: RecalculatedStats,
+    registry: Res<ItemRegistry>,
+    progression: Res<Progression>,
+    classes: Res<Classes>,
 ) {
     for ev in ev_recalculate.read() {
-        if let Ok((mut stats, base_stats)) = stats_query.get_mut(ev.entity) {
-            // 重置为基础属性
+        if let Ok((mut stats, base_stats, equipment, extra, class)) = stats_query.get_mut(ev.entity) {
+            let progression = classes.progression_for(class.map(|c| c.0.as_str()), &progression);
             let current_hp = stats.hp; // 保持当前血量
-            stats.max_hp = base_stats.max_hp;
-            stats.atk = base_stats.atk;
-            stats.def = base_stats.def;
-            stats.rng = base_stats.rng;
-
-            // 应用装备加成
-            apply_equipment_bonuses(&mut stats, &equipment);
-
-            // 如果最大血量增加，按比例恢复血量
-            if stats.max_hp > base_stats.max_hp {
-                let hp_ratio = current_hp as f32 / base_stats.max_hp as f32;
-                stats.hp = (stats.max_hp as f32 * hp_ratio).ceil() as i32;
-                if stats.hp > stats.max_hp {
-                    stats.hp = stats.max_hp;
-                }
+            let old_max_hp = stats.max_hp;
+            let leveled_up = base_stats.lv > stats.lv;
+            stats.lv = base_stats.lv;
+            stats.exp = base_stats.exp;
+            let modifiers =
+                collect_modifiers(base_stats.lv, equipment, extra, &registry, &progression);
+            for stat in Stat::ALL {
+                let value = evaluate(stat, stat.base(base_stats), &modifiers).value;
+                stat.set(&mut stats, value);
+            }
+
+            if leveled_up {
+                // 升级时恢复满血
+                stats.hp = stats.max_hp;
+            } else if stats.max_hp > old_max_hp && old_max_hp > 0 {
+                // 最大血量增加时按比例恢复血量；相对重算前的上限计算，重复重算不会改变血量
+                let hp_ratio = current_hp as f32 / old_max_hp as f32;
+                stats.hp = ((stats.max_hp as f32 * hp_ratio).ceil() as i32).min(stats.max_hp);
             } else {
+                // 包括刚读档（重算前 max_hp 为 0）：生命值只截到新的上限
                 stats.hp = current_hp.min(stats.max_hp);
             }
         }
     }
 }
 
-/// 应用装备属性加成
-fn apply_equipment_bonuses(stats: &mut Stats, equipment: &Equipment) {
-    // 头部装备加成
-    if let Some(head) = &equipment.head {
-        stats.max_hp += head.proto.max_hp;
-        stats.atk += head.proto.atk;
-        stats.def += head.proto.def;
-        stats.rng += head.proto.rng;
-    }
+/// 处理添加修正事件
+pub fn handle_add_modifier(
+    mut ev_add: EventReader<AddModifier>,
+    mut ev_recalculate: EventWriter<RecalculateStats>,
+    targets: Targets,
+    mut modifiers_query: Query<&mut StatModifiers>,
+) {
+    for ev in ev_add.read() {
+        let source: ModifierSource = match ev.source.parse() {
+            Ok(source) => source,
+            Err(err) => {
+                crate::interface::debug_cli::queue_game_log(err);
+                continue;
+            }
+        };
+        let resolved = targets.resolve(&ev.target);
+        if resolved.is_empty() {
+            crate::interface::debug_cli::queue_game_log(format!("未找到目标 {}", ev.target));
+        }
 
-    // 身体装备加成
-    if let Some(body) = &equipment.body {
-        stats.max_hp += body.proto.max_hp;
-        stats.atk += body.proto.atk;
-        stats.def += body.proto.def;
-        stats.rng += body.proto.rng;
+        for entity in resolved {
+            let Ok(mut modifiers) = modifiers_query.get_mut(entity) else {
+                continue;
+            };
+            modifiers.add(StatModifier::new(ev.stat, ev.op, source.clone()));
+            ev_recalculate.write(RecalculateStats { entity });
+            crate::interface::debug_cli::queue_game_log(format!(
+                "{}获得修正 {} {}（{}）",
+                targets.label(entity),
+                ev.stat.label(),
+                ev.op,
+                source.label()
+            ));
+        }
     }
+}
 
-    // 武器加成
-    if let Some(weapon) = &equipment.weapon {
-        stats.max_hp += weapon.proto.max_hp;
-        stats.atk += weapon.proto.atk;
-        stats.def += weapon.proto.def;
-        stats.rng += weapon.proto.rng;
-    }
+/// 处理移除修正事件
+pub fn handle_remove_modifier(
+    mut ev_remove: EventReader<RemoveModifier>,
+    mut ev_recalculate: EventWriter<RecalculateStats>,
+    targets: Targets,
+    mut modifiers_query: Query<&mut StatModifiers>,
+) {
+    for ev in ev_remove.read() {
+        let resolved = targets.resolve(&ev.target);
+        if resolved.is_empty() {
+            crate::interface::debug_cli::queue_game_log(format!("未找到目标 {}", ev.target));
+        }
 
-    // 饰品加成
-    if let Some(accessory) = &equipment.accessory {
-        stats.max_hp += accessory.proto.max_hp;
-        stats.atk += accessory.proto.atk;
-        stats.def += accessory.proto.def;
-        stats.rng += accessory.proto.rng;
+        for entity in resolved {
+            let Ok(mut modifiers) = modifiers_query.get_mut(entity) else {
+                continue;
+            };
+            let label = targets.label(entity);
+            match modifiers.remove_source(&ev.source) {
+                0 => crate::interface::debug_cli::queue_game_log(format!(
+                    "{label}没有来自 {} 的修正",
+                    ev.source
+                )),
+                removed => {
+                    ev_recalculate.write(RecalculateStats { entity });
+                    crate::interface::debug_cli::queue_game_log(format!(
+                        "{label}移除 {removed} 条来自 {} 的修正",
+                        ev.source
+                    ));
+                }
+            }
+        }
     }
 }
 
-/// 显示属性信息
+/// `stats` 需要读取的组件
+type ShownStats<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static Stats,
+        &'static BaseStats,
+        Option<&'static Equipment>,
+        Option<&'static StatModifiers>,
+        Option<&'static SkillPoints>,
+        Option<&'static CharacterClass>,
+    ),
+>;
+
+/// 显示属性信息；`--detail` 时附上每项属性的修正明细
 pub fn show_stats(
     mut ev_show_stats: EventReader<ShowStats>,
     mut ev_log: EventWriter<LogEvent>,
-    player_query: Query<&Stats, With<Player>>,
-    stats_query: Query<&Stats>,
+    targets: Targets,
+    stats_query: ShownStats,
+    registry: Res<ItemRegistry>,
+    progression: Res<Progression>,
+    classes: Res<Classes>,
 ) {
     for ev in ev_show_stats.read() {
-        let stats = if let Some(entity) = ev.entity {
-            stats_query.get(entity).ok()
-        } else {
-            player_query.single().ok()
-        };
+        let found: Vec<_> = targets
+            .resolve(&ev.target)
+            .into_iter()
+            .filter_map(|entity| Some((entity, stats_query.get(entity).ok()?)))
+            .collect();
+
+        if found.is_empty() {
+            ev_log.write(LogEvent("未找到角色属性".to_string()));
+        }
 
-        if let Some(stats) = stats {
-            ev_log.write(LogEvent(format!(
-                "=== 角色属性 ===
+        for (entity, (stats, base_stats, equipment, extra, skill_points, class)) in found {
+            let mut text = format!(
+                "=== {}角色属性 ===
 生命值: {}/{}
 攻击力: {}
 防御力: {}
-等级: {} (经验: {}/{})
-攻击距离: {}
-================",
+等级: {} (经验: {})
+攻击距离: {}",
+                targets.label(entity),
                 stats.hp,
                 stats.max_hp,
                 stats.atk,
                 stats.def,
                 stats.lv,
-                stats.exp,
-                stats.exp_to_next(),
+                stats.exp_label(&progression),
                 stats.rng
-            )));
-        } else {
-            ev_log.write(LogEvent("未找到角色属性".to_string()));
+            );
+            if let Some(SkillPoints(points @ 1..)) = skill_points {
+                text.push_str(&format!("\n技能点: {points}"));
+            }
+            text.push_str("\n================");
+            if ev.detail {
+                let progression = classes.progression_for(class.map(|c| c.0.as_str()), &progression);
+                let modifiers =
+                    collect_modifiers(base_stats.lv, equipment, extra, &registry, &progression);
+                for stat in Stat::ALL {
+                    let breakdown = evaluate(stat, stat.base(base_stats), &modifiers);
+                    text.push('\n');
+                    text.push_str(&breakdown.describe());
+                }
+            }
+            ev_log.write(LogEvent(text));
+        }
+    }
+}
+
+/// 进入职业选择：没有职业数据时直接开始；`--class` 指定了职业时直接以该职业开始；
+/// 否则列出职业，等待 `new_game <class>`
+pub fn enter_character_select(
+    mut commands: Commands,
+    mut next: ResMut<NextState<AppState>>,
+    mut ev_log: EventWriter<LogEvent>,
+    args: Option<Res<LaunchArgs>>,
+    classes: Res<Classes>,
+    registry: Res<ItemRegistry>,
+) {
+    if classes.is_empty() {
+        next.set(AppState::InGame);
+        return;
+    }
+    if let Some(id) = args.as_ref().and_then(|args| args.class.as_ref()) {
+        match classes.get(id) {
+            Some(class) => {
+                commands.insert_resource(ChosenClass(class.id.clone()));
+                next.set(AppState::InGame);
+                return;
+            }
+            None => {
+                ev_log.write(LogEvent(unknown_class(id, &classes)));
+            }
         }
     }
+    ev_log.write(LogEvent(format!(
+        "{}\n输入 new_game <class> 开始游戏",
+        class_list(&classes, &registry, None)
+    )));
 }
 
-/// 初始化玩家实体
-pub fn spawn_player(mut commands: Commands) {
-    let stats = Stats::default();
-    let base_stats = BaseStats::from(&stats);
+/// 处理 `new_game`：只能在职业选择阶段使用
+pub fn start_new_game(
+    mut commands: Commands,
+    mut ev_new_game: EventReader<NewGame>,
+    mut next: ResMut<NextState<AppState>>,
+    mut ev_log: EventWriter<LogEvent>,
+    state: Res<State<AppState>>,
+    classes: Res<Classes>,
+) {
+    for ev in ev_new_game.read() {
+        if *state.get() != AppState::CharacterSelect {
+            ev_log.write(LogEvent("游戏已经开始，不能重新选择职业".to_string()));
+            continue;
+        }
+        let Some(class) = classes.get(&ev.class) else {
+            ev_log.write(LogEvent(unknown_class(&ev.class, &classes)));
+            continue;
+        };
+        commands.insert_resource(ChosenClass(class.id.clone()));
+        next.set(AppState::InGame);
+        ev_log.write(LogEvent(format!("✔ 以{}开始新游戏", class.name)));
+        // 同一帧里的后续 new_game 不再生效
+        break;
+    }
+}
 
-    commands.spawn((Player, stats, base_stats));
+fn unknown_class(id: &str, classes: &Classes) -> String {
+    let ids: Vec<&str> = classes.classes.iter().map(|class| class.id.as_str()).collect();
+    format!("未知职业 {id}，可选: {}", ids.join(", "))
+}
+
+/// 职业列表；`current` 为玩家当前职业
+fn class_list(classes: &Classes, registry: &ItemRegistry, current: Option<&str>) -> String {
+    let mut text = String::from("职业列表:");
+    for class in &classes.classes {
+        text.push('\n');
+        text.push_str(&class.describe(registry));
+        if current == Some(class.id.as_str()) {
+            text.push_str("\n  （当前职业）");
+        }
+    }
+    text
+}
+
+/// 显示可选职业
+pub fn show_classes(
+    mut ev_show: EventReader<ShowClasses>,
+    mut ev_log: EventWriter<LogEvent>,
+    classes: Res<Classes>,
+    registry: Res<ItemRegistry>,
+    player: Query<&CharacterClass, With<Player>>,
+) {
+    for _ in ev_show.read() {
+        if classes.is_empty() {
+            ev_log.write(LogEvent("没有定义职业".to_string()));
+            continue;
+        }
+        let current = player.single().ok().map(|class| class.0.as_str());
+        ev_log.write(LogEvent(class_list(&classes, &registry, current)));
+    }
+}
+
+/// 初始化玩家实体（自带背包与装备栏）：有职业时按职业的基础属性与开局物品生成
+pub fn spawn_player(
+    mut commands: Commands,
+    mut ev_recalculate: EventWriter<RecalculateStats>,
+    chosen: Option<Res<ChosenClass>>,
+    classes: Res<Classes>,
+    registry: Res<ItemRegistry>,
+) {
+    let class = chosen.and_then(|chosen| classes.get(&chosen.0));
+    let base_stats = class.map_or_else(BaseStats::default, BaseStats::from);
+    let (backpack, equipment) = class.map_or_else(
+        || (Backpack::default(), Equipment::default()),
+        |class| starting_loadout(class, &registry),
+    );
+
+    let mut player = commands.spawn((
+        Player,
+        Faction::Player,
+        Stats::from(&base_stats),
+        base_stats,
+        backpack,
+        equipment,
+        SkillPoints::default(),
+        StatModifiers::default(),
+        StatusEffects::default(),
+    ));
+    if let Some(class) = class {
+        player.insert(CharacterClass(class.id.clone()));
+    }
+    // 开局装备的加成
+    let entity = player.id();
+    ev_recalculate.write(RecalculateStats { entity });
+}
+
+/// 职业的开局背包与装备（物品已在加载时校验）
+fn starting_loadout(class: &ClassDef, registry: &ItemRegistry) -> (Backpack, Equipment) {
+    let mut backpack = Backpack::default();
+    for ((id, count), slot) in class.items.iter().zip(backpack.slots.iter_mut()) {
+        if let Some(item) = registry.by_id(id) {
+            *slot = ItemStack::new(item, *count);
+        }
+    }
+
+    let mut equipment = Equipment::default();
+    for id in &class.equipped {
+        if let Some(item) = registry.by_id(id)
+            && let Some(slot) = registry.get(item).kind.equip_slot()
+            && let Some(slot) = equipment.get_slot_mut(slot)
+        {
+            *slot = Some(ItemStack::new(item, 1));
+        }
+    }
+    (backpack, equipment)
+}
+
+/// 生成非玩家角色：与玩家同样的默认属性、背包和装备栏
+pub fn spawn_actor(mut ev_spawn: EventReader<SpawnActor>, mut commands: Commands) {
+    for ev in ev_spawn.read() {
+        let stats = Stats::default();
+        let base_stats = BaseStats::from(&stats);
+
+        let entity = commands
+            .spawn((
+                Name::new(ev.name.clone()),
+                ev.faction,
+                stats,
+                base_stats,
+                Backpack::default(),
+                Equipment::default(),
+                SkillPoints::default(),
+                StatModifiers::default(),
+                StatusEffects::default(),
+            ))
+            .id();
+
+        crate::interface::debug_cli::queue_game_log(format!(
+            "生成 {} ({})，id = {entity}",
+            ev.name, ev.faction
+        ));
+    }
 }
@@ -0,0 +1,114 @@
+//! 目标解析：事件只描述“作用于谁”，由 [`Targets`] 在处理时解析成具体实体
+//!
+//! CLI 写法（命令的第一个参数，以 `@` 开头）：
+//!
+//! | 写法 | 含义 |
+//! |------|------|
+//! | 省略 / `@player` / `@me` | 玩家 |
+//! | `@3v1` | 指定实体 id |
+//! | `@goblin1` | `Name` 为 goblin1 的实体 |
+//! | `@all:monster` | 阵营内全部实体 |
+
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use std::fmt;
+use std::str::FromStr;
+
+use super::components::{Faction, Player};
+
+/// 事件目标
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum Target {
+    #[default]
+    Player,
+    Entity(Entity),
+    Named(String),
+    Faction(Faction),
+}
+
+impl FromStr for Target {
+    type Err = String;
+
+    fn from_str(raw: &str) -> Result<Self, Self::Err> {
+        let body = raw
+            .strip_prefix('@')
+            .ok_or_else(|| format!("target must start with @, got \"{raw}\""))?;
+
+        if body.is_empty() {
+            return Err("empty target".into());
+        }
+        if body.eq_ignore_ascii_case("player") || body.eq_ignore_ascii_case("me") {
+            return Ok(Target::Player);
+        }
+        if let Some(faction) = body.strip_prefix("all:") {
+            return faction.parse().map(Target::Faction);
+        }
+        if let Some(entity) = parse_entity(body) {
+            return Ok(Target::Entity(entity));
+        }
+        Ok(Target::Named(body.to_string()))
+    }
+}
+
+impl fmt::Display for Target {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Target::Player => write!(f, "@player"),
+            Target::Entity(entity) => write!(f, "@{entity}"),
+            Target::Named(name) => write!(f, "@{name}"),
+            Target::Faction(faction) => write!(f, "@all:{faction}"),
+        }
+    }
+}
+
+/// 按 `Entity` 的 Display 格式（`3v1`）解析；必须写出 generation，纯数字留给名字（如名为 `42` 的角色）
+pub fn parse_entity(raw: &str) -> Option<Entity> {
+    let (index, generation) = raw.split_once('v')?;
+    let (index, generation) = (index.parse::<u32>().ok()?, generation.parse::<u32>().ok()?);
+    if generation == 0 {
+        return None;
+    }
+    Entity::try_from_bits(((generation as u64) << 32) | index as u64).ok()
+}
+
+/// 目标解析器：只读 `Player` / `Name` / `Faction`，可以和任意 `&mut Stats` 查询并存
+#[derive(SystemParam)]
+pub struct Targets<'w, 's> {
+    players: Query<'w, 's, Entity, With<Player>>,
+    named: Query<'w, 's, (Entity, &'static Name)>,
+    factions: Query<'w, 's, (Entity, &'static Faction)>,
+    entities: Query<'w, 's, Entity>,
+}
+
+impl Targets<'_, '_> {
+    /// 解析为实体列表；找不到时为空
+    pub fn resolve(&self, target: &Target) -> Vec<Entity> {
+        match target {
+            Target::Player => self.players.iter().collect(),
+            Target::Entity(entity) => self.entities.get(*entity).into_iter().collect(),
+            Target::Named(name) => self
+                .named
+                .iter()
+                .filter(|(_, n)| n.as_str().eq_ignore_ascii_case(name))
+                .map(|(entity, _)| entity)
+                .collect(),
+            Target::Faction(faction) => self
+                .factions
+                .iter()
+                .filter(|(_, f)| *f == faction)
+                .map(|(entity, _)| entity)
+                .collect(),
+        }
+    }
+
+    /// 日志里称呼实体：玩家为空，其他实体用名字或 id
+    pub fn label(&self, entity: Entity) -> String {
+        if self.players.contains(entity) {
+            String::new()
+        } else if let Ok((_, name)) = self.named.get(entity) {
+            format!("{name} ")
+        } else {
+            format!("{entity} ")
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use crate::save::tool::SaveTool;
+use bevy::prelude::*;
+use std::io::IsTerminal;
+use std::path::PathBuf;
+
+/// 批处理命令来源
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScriptSource {
+    File(PathBuf),
+    Stdin,
+}
+
+/// 启动参数（`cargo run -- <args>`）
+#[derive(Resource, Debug, Clone, Default)]
+pub struct LaunchArgs {
+    /// 无窗口 / 无 GPU 运行：MinimalPlugins + AssetPlugin + StatesPlugin
+    pub headless: bool,
+    /// 批处理：逐行执行脚本后退出；`--script -` 或管道输入时读 stdin
+    pub script: Option<ScriptSource>,
+    /// 批处理 transcript 输出文件，缺省写 stdout
+    pub transcript: Option<PathBuf>,
+    /// 只加载并校验物品数据，打印报告后退出（给内容作者用）
+    pub validate_data: bool,
+    /// 离线存档工具（`save inspect|migrate ...`），执行后直接退出，不启动游戏
+    pub save_tool: Option<SaveTool>,
+    /// 随机数种子（`--seed 123`），相同种子 + 相同命令得到相同结果
+    pub seed: Option<u64>,
+    /// 把接受的命令录制到回放文件（`--record <file>`）
+    pub record: Option<PathBuf>,
+    /// 按回放文件重放命令后退出（`--replay <file>`），种子取自回放文件
+    pub replay: Option<PathBuf>,
+    /// 回放时核对状态哈希，报告第一次分歧（`--verify`）
+    pub verify: bool,
+    /// 跳过职业选择，直接以该职业开始（`--class warrior`）
+    pub class: Option<String>,
+}
+
+impl LaunchArgs {
+    /// 从进程参数解析；stdin 不是终端（管道 / 重定向）时自动进入批处理
+    pub fn from_env() -> Result<Self, String> {
+        let mut args = Self::parse(std::env::args().skip(1))?;
+        if args.script.is_none()
+            && args.replay.is_none()
+            && !args.validate_data
+            && args.save_tool.is_none()
+            && !std::io::stdin().is_terminal() {
+            args.script = Some(ScriptSource::Stdin);
+        }
+        Ok(args)
+    }
+
+    pub fn parse<I, S>(args: I) -> Result<Self, String>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mut out = Self {
+            // 未启用 window feature 时只能 headless
+            headless: !cfg!(feature = "window"),
+            ..default()
+        };
+
+        let mut args = args.into_iter().map(Into::into);
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--headless" => out.headless = true,
+                "--validate-data" => {
+                    out.validate_data = true;
+                    out.headless = true;
+                }
+                "--script" => {
+                    let path = args.next().ok_or("--script 需要文件路径（- 表示 stdin）")?;
+                    out.script = Some(if path == "-" {
+                        ScriptSource::Stdin
+                    } else {
+                        ScriptSource::File(path.into())
+                    });
+                }
+                "--transcript" => {
+                    let path = args.next().ok_or("--transcript 需要文件路径")?;
+                    out.transcript = Some(path.into());
+                }
+                "--seed" => {
+                    let seed = args.next().ok_or("--seed 需要一个非负整数")?;
+                    let seed = seed
+                        .parse()
+                        .map_err(|_| format!("--seed 应为非负整数，得到 \"{seed}\""))?;
+                    out.seed = Some(seed);
+                }
+                "--record" => {
+                    let path = args.next().ok_or("--record 需要文件路径")?;
+                    out.record = Some(path.into());
+                }
+                "--replay" => {
+                    let path = args.next().ok_or("--replay 需要文件路径")?;
+                    out.replay = Some(path.into());
+                }
+                "--verify" => out.verify = true,
+                "--class" => {
+                    let class = args.next().ok_or("--class 需要职业 id")?;
+                    out.class = Some(class);
+                }
+                "save" => out.save_tool = Some(SaveTool::parse(&mut args)?),
+                other => return Err(format!("未知参数: {other}")),
+            }
+        }
+
+        if out.replay.is_some() && out.script.is_some() {
+            return Err("--replay 不能与 --script 同时使用".into());
+        }
+        if out.replay.is_some() && out.record.is_some() {
+            return Err("--replay 不能与 --record 同时使用".into());
+        }
+        if out.verify && out.replay.is_none() {
+            return Err("--verify 只能与 --replay 一起使用".into());
+        }
+        Ok(out)
+    }
+}
@@ -0,0 +1,17 @@
+//! 稳定哈希：结果只取决于输入字节，跨进程、跨平台、跨编译器版本一致
+//! （`std` 的 `DefaultHasher` 不保证这一点），用于回放文件中的数据与状态校验
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0100_0000_01b3;
+
+/// 64 位 FNV-1a
+pub fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(FNV_OFFSET, |hash, &b| {
+        (hash ^ u64::from(b)).wrapping_mul(FNV_PRIME)
+    })
+}
+
+/// 十六进制形式，写入 JSON 时不受数字精度影响
+pub fn fnv1a_hex(bytes: &[u8]) -> String {
+    format!("{:016x}", fnv1a(bytes))
+}
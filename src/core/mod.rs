@@ -3,6 +3,9 @@ use bevy::prelude::*;
 pub mod states;
 pub mod events;
 pub mod resources;
+pub mod args;
+pub mod hash;
+pub mod rng;
 
 /// 核心插件：注册全局资源 / 事件 / 状态
 pub struct CorePlugin;
@@ -11,11 +14,20 @@ impl Plugin for CorePlugin {
     fn build(&self, app: &mut App) {
         use states::AppState;
 
+        // 随机数种子：`--seed` 指定时可复现，否则取当前时间
+        let seed = app
+            .world()
+            .get_resource::<args::LaunchArgs>()
+            .and_then(|args| args.seed);
+        let rng = seed.map_or_else(rng::GameRng::from_entropy, rng::GameRng::new);
+
         // 插件首次载入时，插入初始 State
         app
             .init_state::<AppState>()
             .add_event::<events::LogEvent>()          // 示例事件
             .init_resource::<resources::GameConfig>() // 示例资源
+            .init_resource::<resources::GameTurn>()
+            .insert_resource(rng)
             .add_systems(Startup, events::hello_world);
     }
 }
\ No newline at end of file
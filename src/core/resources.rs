@@ -1,6 +1,32 @@
 use bevy::prelude::*;
+use std::path::PathBuf;
+use std::time::Duration;
 
-#[derive(Resource, Default)]
+#[derive(Resource)]
 pub struct GameConfig {
     pub _tick_rate: f32,
-}
\ No newline at end of file
+    /// 数据加载超时，超过后进入 `AppState::LoadFailed`
+    pub load_timeout: Duration,
+    /// 存档目录（`save <slot>` 写入 `<save_dir>/<slot>.json`）
+    pub save_dir: PathBuf,
+    /// 每执行多少条游戏命令自动存档一次（0 = 只在升级 / 状态切换 / 退出时存）
+    pub autosave_every: u32,
+    /// 自动存档保留的历史份数（`autosave-1` 最新）
+    pub autosave_backups: usize,
+}
+
+/// 游戏回合：每执行一条游戏命令 +1（状态效果按回合计时）
+#[derive(Resource, Debug, Default)]
+pub struct GameTurn(pub u64);
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        Self {
+            _tick_rate: 0.0,
+            load_timeout: Duration::from_secs(10),
+            save_dir: PathBuf::from("saves"),
+            autosave_every: 10,
+            autosave_backups: 3,
+        }
+    }
+}
@@ -0,0 +1,122 @@
+//! 可复现的随机数：所有玩法随机都从 `GameRng` 的具名子流取
+//!
+//! 每个子流是同一种子下独立的 ChaCha8 流，某个系统多取一次随机数不会让其他系统的
+//! 结果错位。状态（种子 + 各子流位置）随存档保存，读档后随机序列从存档处继续
+
+use bevy::prelude::*;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+
+/// 随机数子流
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RngStream {
+    /// 命中 / 暴击判定
+    Combat,
+    /// 掉落
+    Loot,
+    /// 地图生成
+    Mapgen,
+    /// 敌人行为
+    Ai,
+}
+
+impl RngStream {
+    pub const ALL: [RngStream; 4] = [
+        RngStream::Combat,
+        RngStream::Loot,
+        RngStream::Mapgen,
+        RngStream::Ai,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            RngStream::Combat => "combat",
+            RngStream::Loot => "loot",
+            RngStream::Mapgen => "mapgen",
+            RngStream::Ai => "ai",
+        }
+    }
+
+    /// ChaCha 流编号；已发布的编号不能改，否则旧种子的结果会变
+    fn id(self) -> u64 {
+        match self {
+            RngStream::Combat => 1,
+            RngStream::Loot => 2,
+            RngStream::Mapgen => 3,
+            RngStream::Ai => 4,
+        }
+    }
+}
+
+/// 全局随机数源
+#[derive(Resource, Debug, Clone)]
+pub struct GameRng {
+    seed: u64,
+    streams: [ChaCha8Rng; RngStream::ALL.len()],
+}
+
+/// 存档中的随机数状态：各子流已消耗的位置（ChaCha 的 word 位置）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RngState {
+    pub seed: u64,
+    pub combat: u128,
+    pub loot: u128,
+    pub mapgen: u128,
+    pub ai: u128,
+}
+
+impl GameRng {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            streams: RngStream::ALL.map(|stream| {
+                let mut rng = ChaCha8Rng::seed_from_u64(seed);
+                rng.set_stream(stream.id());
+                rng
+            }),
+        }
+    }
+
+    /// 未指定种子时取当前时间
+    pub fn from_entropy() -> Self {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_nanos() as u64);
+        Self::new(nanos)
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// 取子流；配合 `rand::Rng` 使用：`rng.stream(RngStream::Combat).gen_range(0..100)`
+    pub fn stream(&mut self, stream: RngStream) -> &mut ChaCha8Rng {
+        &mut self.streams[stream as usize]
+    }
+
+    pub fn state(&self) -> RngState {
+        let pos = |stream: RngStream| self.streams[stream as usize].get_word_pos();
+        RngState {
+            seed: self.seed,
+            combat: pos(RngStream::Combat),
+            loot: pos(RngStream::Loot),
+            mapgen: pos(RngStream::Mapgen),
+            ai: pos(RngStream::Ai),
+        }
+    }
+
+    pub fn from_state(state: &RngState) -> Self {
+        let mut rng = Self::new(state.seed);
+        for (stream, pos) in [
+            (RngStream::Combat, state.combat),
+            (RngStream::Loot, state.loot),
+            (RngStream::Mapgen, state.mapgen),
+            (RngStream::Ai, state.ai),
+        ] {
+            rng.stream(stream).set_word_pos(pos);
+        }
+        rng
+    }
+}
@@ -6,6 +6,15 @@ pub enum AppState {
     #[default]
     Startup,
     Loading,
+    /// 数据加载完成，等待 `new_game <class>` 选择职业（没有职业数据时直接进入游戏）
+    CharacterSelect,
     InGame,
+    /// 数据加载 / 校验失败：展示错误后以非零退出码结束
+    LoadFailed,
     _Shutdown,
-}
\ No newline at end of file
+}
+
+/// 运行条件：CLI 接受输入（职业选择与游戏中）
+pub fn accepts_input(state: Option<Res<State<AppState>>>) -> bool {
+    state.is_some_and(|state| matches!(state.get(), AppState::CharacterSelect | AppState::InGame))
+}
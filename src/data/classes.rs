@@ -0,0 +1,220 @@
+//! 职业（`data/classes.ron`）：基础属性、成长、可用装备类型与开局物品
+//!
+//! 由清单的 `classes` 字段指定，随物品数据一起加载与热重载。开局时在职业选择阶段用
+//! `new_game <class>` 选择（或 `--class <id>` 直接指定）；清单未指定时没有职业选择，
+//! 玩家使用默认属性。
+//!
+//! ```text
+//! (
+//!   classes: [
+//!     (
+//!       id: "warrior", name: "战士", description: "近战，血厚",
+//!       base: (max_hp: 28, atk: 3, def: 2, rng: 1),
+//!       growth: (max_hp: 4, atk: 1, def: 1),
+//!       allowed: [OneHanded, TwoHanded, Head, Body, Accessory],
+//!       items: [("potion_heal_small", 2)],
+//!       equipped: ["sword_iron"],
+//!     ),
+//!   ],
+//! )
+//! ```
+
+use bevy::asset::Asset;
+use bevy::prelude::Resource;
+use bevy::reflect::TypePath;
+use serde::Deserialize;
+use std::borrow::Cow;
+use std::collections::HashSet;
+
+use super::progression::{Progression, StatGrowth};
+use super::registry::ItemRegistry;
+use super::schema::{ArmorSlot, ItemKind, implicit_some};
+
+/// 职业表：既是资产（从文件加载），也是资源（加载并校验后插入）
+#[derive(Asset, TypePath, Resource, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Classes {
+    pub classes: Vec<ClassDef>,
+}
+
+/// 一个职业
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ClassDef {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// 1 级的基础属性
+    pub base: ClassStats,
+    /// 每升一级的属性成长，替代成长曲线的默认成长（等级奖励的成长仍然优先）
+    #[serde(default, deserialize_with = "implicit_some")]
+    pub growth: Option<StatGrowth>,
+    /// 可装备的类型；缺省为全部
+    #[serde(default = "EquipType::all")]
+    pub allowed: Vec<EquipType>,
+    /// 开局背包中的物品：(物品 id, 数量)
+    #[serde(default)]
+    pub items: Vec<(String, u32)>,
+    /// 开局已装备的物品 id
+    #[serde(default)]
+    pub equipped: Vec<String>,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ClassStats {
+    pub max_hp: i32,
+    pub atk: i32,
+    pub def: i32,
+    pub rng: i32,
+}
+
+/// 装备类型：武器按单手 / 双手区分，防具按槽位区分
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EquipType {
+    OneHanded,
+    TwoHanded,
+    Head,
+    Body,
+    Accessory,
+}
+
+impl EquipType {
+    pub fn all() -> Vec<Self> {
+        vec![
+            EquipType::OneHanded,
+            EquipType::TwoHanded,
+            EquipType::Head,
+            EquipType::Body,
+            EquipType::Accessory,
+        ]
+    }
+
+    /// 物品的装备类型；不可装备的物品为 None
+    pub fn of(kind: &ItemKind) -> Option<Self> {
+        match kind {
+            ItemKind::Weapon { hands } if *hands >= 2 => Some(EquipType::TwoHanded),
+            ItemKind::Weapon { .. } => Some(EquipType::OneHanded),
+            ItemKind::Armor { slot: ArmorSlot::Head } => Some(EquipType::Head),
+            ItemKind::Armor { slot: ArmorSlot::Body } => Some(EquipType::Body),
+            ItemKind::Armor { slot: ArmorSlot::Accessory } => Some(EquipType::Accessory),
+            _ => None,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            EquipType::OneHanded => "单手武器",
+            EquipType::TwoHanded => "双手武器",
+            EquipType::Head => "头部防具",
+            EquipType::Body => "身体防具",
+            EquipType::Accessory => "饰品",
+        }
+    }
+}
+
+impl Classes {
+    pub fn get(&self, id: &str) -> Option<&ClassDef> {
+        self.classes.iter().find(|class| class.id == id)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.classes.is_empty()
+    }
+
+    /// 某职业实际使用的成长曲线；没有职业或职业不存在时为 `base` 本身
+    pub fn progression_for<'a>(&self, class: Option<&str>, base: &'a Progression) -> Cow<'a, Progression> {
+        match class.and_then(|id| self.get(id)).and_then(|class| class.growth) {
+            Some(growth) => Cow::Owned(Progression {
+                growth,
+                ..base.clone()
+            }),
+            None => Cow::Borrowed(base),
+        }
+    }
+
+    /// 反序列化无法表达的问题；`registry` 用于检查开局物品
+    pub fn validate(&self, registry: &ItemRegistry) -> Vec<String> {
+        let mut problems = Vec::new();
+        if self.classes.is_empty() {
+            problems.push("没有定义任何职业".into());
+        }
+
+        let mut seen = HashSet::new();
+        for class in &self.classes {
+            let id = &class.id;
+            if !seen.insert(id.as_str()) {
+                problems.push(format!("职业 {id} 重复"));
+            }
+            if class.base.max_hp <= 0 {
+                problems.push(format!("职业 {id} 的 max_hp 应为正数，得到 {}", class.base.max_hp));
+            }
+            if class.allowed.is_empty() {
+                problems.push(format!("职业 {id} 的 allowed 为空，无法装备任何物品"));
+            }
+            for (item, count) in &class.items {
+                if registry.by_id(item).is_none() {
+                    problems.push(format!("职业 {id} 的开局物品 {item} 不存在"));
+                }
+                if *count == 0 {
+                    problems.push(format!("职业 {id} 的开局物品 {item} 数量为 0"));
+                }
+            }
+
+            let mut slots = HashSet::new();
+            for item in &class.equipped {
+                let Some(entry) = registry.by_id(item).map(|handle| registry.get(handle)) else {
+                    problems.push(format!("职业 {id} 的开局装备 {item} 不存在"));
+                    continue;
+                };
+                let (Some(slot), Some(kind)) = (entry.kind.equip_slot(), EquipType::of(&entry.kind)) else {
+                    problems.push(format!("职业 {id} 的开局装备 {item} 无法装备"));
+                    continue;
+                };
+                if !class.allowed.contains(&kind) {
+                    problems.push(format!("职业 {id} 不能装备开局装备 {item}（{}）", kind.label()));
+                }
+                if !slots.insert(slot) {
+                    problems.push(format!("职业 {id} 的开局装备 {item} 与其它装备同占 {slot} 槽"));
+                }
+            }
+        }
+        problems
+    }
+}
+
+impl ClassDef {
+    pub fn allows(&self, kind: EquipType) -> bool {
+        self.allowed.contains(&kind)
+    }
+
+    /// `classes` 列表中的一项
+    pub fn describe(&self, registry: &ItemRegistry) -> String {
+        let b = &self.base;
+        let mut out = format!("{} ({})", self.name, self.id);
+        if !self.description.is_empty() {
+            out.push_str(&format!(" - {}", self.description));
+        }
+        out.push_str(&format!(
+            "\n  属性: 生命值 {}，攻击力 {}，防御力 {}，攻击距离 {}",
+            b.max_hp, b.atk, b.def, b.rng
+        ));
+        if let Some(growth) = &self.growth {
+            out.push_str(&format!("\n  每级: {}", growth.describe()));
+        }
+        let allowed: Vec<&str> = self.allowed.iter().map(EquipType::label).collect();
+        out.push_str(&format!("\n  装备: {}", allowed.join("、")));
+        let name = |id: &str| {
+            registry
+                .by_id(id)
+                .map_or_else(|| id.to_string(), |item| registry.get(item).name.clone())
+        };
+        let mut loadout: Vec<String> = self.equipped.iter().map(|id| format!("{}（已装备）", name(id))).collect();
+        loadout.extend(self.items.iter().map(|(id, count)| format!("{} ×{count}", name(id))));
+        if !loadout.is_empty() {
+            out.push_str(&format!("\n  开局: {}", loadout.join("，")));
+        }
+        out
+    }
+}
@@ -0,0 +1,34 @@
+use super::events::*;
+use crate::interface::command::{
+    ArgKind, ArgSpec, CliCommand, CommandArgs, CommandSpec, CommandType,
+};
+
+impl CliCommand for ReloadData {
+    fn spec() -> CommandSpec {
+        CommandSpec::new("reload", CommandType::System, "重新加载物品数据")
+    }
+
+    fn from_args(_: &CommandArgs) -> Self {
+        Self
+    }
+}
+
+impl CliCommand for ItemsCsv {
+    fn spec() -> CommandSpec {
+        CommandSpec::new("data", CommandType::System, "导入 / 导出 CSV 物品表")
+            .arg(ArgSpec::required(
+                "action",
+                ArgKind::Choice(&["import", "export"]),
+                "import 合并到当前会话 / export 导出注册表",
+            ))
+            .arg(ArgSpec::required("csv", ArgKind::Word, "CSV 文件路径"))
+    }
+
+    fn from_args(args: &CommandArgs) -> Self {
+        let path = args.get::<String>("csv");
+        match args.get::<String>("action").as_str() {
+            "import" => ItemsCsv::Import(path),
+            _ => ItemsCsv::Export(path),
+        }
+    }
+}
@@ -0,0 +1,464 @@
+//! CSV 物品表：表格软件导出的平衡表直接作为物品文件使用，也可从注册表导出回 CSV
+//!
+//! 第一行是表头，列名忽略大小写、顺序任意：
+//!
+//! ```text
+//! id,name,atk,def,max_hp,rng,heal,item_type,hands,slot,effect,door_id
+//! sword_iron,铁剑,10,0,0,0,,weapon,1,,,
+//! potion_heal_small,小型治疗药水,0,0,0,0,25,potion,,,,
+//! scroll_regen,回春卷轴,0,0,0,0,,scroll,,,"(id:""regen"",name:""回春"",duration:Turns(3),tick_heal:2)",
+//! ```
+//!
+//! `id` / `name` / `item_type` 必须有；数值列留空为 0；`heal` / `hands` / `slot` /
+//! `effect` / `door_id` 只能填在对应类型的行上，`effect` 是 RON 写法的效果声明。问题按 `行:列`（列为第几列）报告，
+//! 格式与 [`ValidationReport`] 相同
+
+use bevy::asset::ron;
+
+use super::schema::{ArmorSlot, EffectSpec, ItemEntry, ItemKind, ItemList};
+use super::validate::{ItemIssue, SourceSpan, ValidationReport};
+
+/// 导出时的列顺序（也是全部可用的列）
+pub const CSV_COLUMNS: &[&str] = &[
+    "id",
+    "name",
+    "atk",
+    "def",
+    "max_hp",
+    "rng",
+    "heal",
+    "item_type",
+    "hands",
+    "slot",
+    "effect",
+    "door_id",
+];
+
+const REQUIRED_COLUMNS: &[&str] = &["id", "name", "item_type"];
+
+const ITEM_TYPES: &str = "weapon/armor/potion/scroll/key/misc";
+
+/// 表头问题没有对应物品，用它代替 id
+const HEADER: &str = "(表头)";
+
+/// 一条记录及其起始行号
+struct Record {
+    line: usize,
+    cells: Vec<String>,
+}
+
+/// 解析 CSV 物品表；有问题时返回全部问题
+pub fn parse_items(src: &str, source: &str) -> Result<ItemList, ValidationReport> {
+    let mut issues = Vec::new();
+    let report = |issues| ValidationReport {
+        source: source.to_string(),
+        issues,
+    };
+
+    let src = src.strip_prefix('\u{feff}').unwrap_or(src);
+    let records = match read_records(src) {
+        Ok(records) => records,
+        Err(span) => {
+            push(
+                &mut issues,
+                HEADER,
+                span.line,
+                span.column,
+                "引号未闭合".into(),
+            );
+            return Err(report(issues));
+        }
+    };
+    let mut records = records.into_iter();
+    let Some(header) = records.next() else {
+        push(&mut issues, HEADER, 1, 1, "缺少表头".into());
+        return Err(report(issues));
+    };
+
+    // 表头：列名 → 列下标
+    let mut columns: Vec<Option<&'static str>> = Vec::new();
+    for (idx, raw) in header.cells.iter().enumerate() {
+        let name = raw.trim().to_lowercase();
+        let known = CSV_COLUMNS.iter().copied().find(|c| *c == name);
+        match known {
+            Some(c) if columns.contains(&Some(c)) => {
+                push(
+                    &mut issues,
+                    HEADER,
+                    header.line,
+                    idx + 1,
+                    format!("重复的列 \"{c}\""),
+                );
+            }
+            Some(_) => {}
+            None => push(
+                &mut issues,
+                HEADER,
+                header.line,
+                idx + 1,
+                format!(
+                    "未知列 \"{}\"（可用: {}）",
+                    raw.trim(),
+                    CSV_COLUMNS.join(",")
+                ),
+            ),
+        }
+        columns.push(known);
+    }
+    for required in REQUIRED_COLUMNS {
+        if !columns.contains(&Some(required)) {
+            push(
+                &mut issues,
+                HEADER,
+                header.line,
+                1,
+                format!("缺少列 \"{required}\""),
+            );
+        }
+    }
+    if !issues.is_empty() {
+        return Err(report(issues));
+    }
+    let index_of = |name: &str| columns.iter().position(|c| *c == Some(name));
+    let id_column = index_of("id").unwrap_or_default();
+
+    let mut list = ItemList {
+        items: Vec::new(),
+        source: source.to_string(),
+        spans: Vec::new(),
+        hash: String::new(),
+    };
+    for record in records {
+        if record.cells.iter().all(|cell| cell.trim().is_empty()) {
+            continue;
+        }
+        let id = record.cells.get(id_column).map_or("", |s| s.trim());
+        if record.cells.len() > columns.len() {
+            push(
+                &mut issues,
+                id,
+                record.line,
+                columns.len() + 1,
+                format!(
+                    "列数 {} 多于表头的 {} 列",
+                    record.cells.len(),
+                    columns.len()
+                ),
+            );
+            continue;
+        }
+
+        // 取单元格：(列号, 去掉首尾空白的内容)，缺少的列视为空
+        let cell = |name: &str| {
+            let idx = index_of(name)?;
+            let value = record.cells.get(idx).map_or("", |s| s.trim());
+            Some((idx + 1, value))
+        };
+        let filled = |name: &str| cell(name).filter(|(_, value)| !value.is_empty());
+
+        let mut int = |name: &str| -> i32 {
+            let Some((column, raw)) = filled(name) else {
+                return 0;
+            };
+            raw.parse().unwrap_or_else(|_| {
+                push(
+                    &mut issues,
+                    id,
+                    record.line,
+                    column,
+                    format!("{name} 应为整数，得到 \"{raw}\""),
+                );
+                0
+            })
+        };
+        let atk = int("atk");
+        let def = int("def");
+        let max_hp = int("max_hp");
+        let rng = int("rng");
+        let heal = int("heal");
+
+        let text = |name: &str| cell(name).map_or(String::new(), |(_, value)| value.to_string());
+        let (type_column, item_type) = cell("item_type").unwrap_or_default();
+        let kind = match item_type.to_lowercase().as_str() {
+            "weapon" => {
+                let hands = match filled("hands") {
+                    None => Some(1),
+                    Some((column, raw)) => raw.parse().ok().or_else(|| {
+                        push(
+                            &mut issues,
+                            id,
+                            record.line,
+                            column,
+                            format!("hands 应为整数，得到 \"{raw}\""),
+                        );
+                        None
+                    }),
+                };
+                hands.map(|hands| ItemKind::Weapon { hands })
+            }
+            "armor" => match filled("slot") {
+                None => {
+                    push(
+                        &mut issues,
+                        id,
+                        record.line,
+                        type_column,
+                        "armor 需要填写 slot".into(),
+                    );
+                    None
+                }
+                Some((column, raw)) => parse_slot(raw)
+                    .map(|slot| ItemKind::Armor { slot })
+                    .or_else(|| {
+                        push(
+                            &mut issues,
+                            id,
+                            record.line,
+                            column,
+                            format!("未知的 slot \"{raw}\"（可选 head/body/accessory）"),
+                        );
+                        None
+                    }),
+            },
+            "potion" => match filled("heal") {
+                None => {
+                    push(
+                        &mut issues,
+                        id,
+                        record.line,
+                        type_column,
+                        "potion 需要填写 heal".into(),
+                    );
+                    None
+                }
+                Some(_) => Some(ItemKind::Potion { heal }),
+            },
+            "scroll" => match filled("effect") {
+                None => {
+                    push(
+                        &mut issues,
+                        id,
+                        record.line,
+                        type_column,
+                        "scroll 需要填写 effect".into(),
+                    );
+                    None
+                }
+                Some((column, raw)) => match ron::from_str::<EffectSpec>(raw) {
+                    Ok(effect) => Some(ItemKind::Scroll { effect }),
+                    Err(err) => {
+                        push(
+                            &mut issues,
+                            id,
+                            record.line,
+                            column,
+                            format!("effect 不是有效的效果声明: {err}"),
+                        );
+                        None
+                    }
+                },
+            },
+            "key" => Some(ItemKind::Key {
+                door_id: text("door_id"),
+            }),
+            "misc" => Some(ItemKind::Misc),
+            "" => {
+                push(
+                    &mut issues,
+                    id,
+                    record.line,
+                    type_column,
+                    "item_type 不能为空".into(),
+                );
+                None
+            }
+            other => {
+                push(
+                    &mut issues,
+                    id,
+                    record.line,
+                    type_column,
+                    format!("未知的 item_type \"{other}\"（可选 {ITEM_TYPES}）"),
+                );
+                None
+            }
+        };
+
+        // 专属列只能填在对应类型上
+        if let Some(kind) = &kind {
+            for (name, owner) in [
+                ("heal", "potion"),
+                ("hands", "weapon"),
+                ("slot", "armor"),
+                ("effect", "scroll"),
+                ("door_id", "key"),
+            ] {
+                if let Some((column, _)) = filled(name)
+                    && kind_name(kind) != owner
+                {
+                    push(
+                        &mut issues,
+                        id,
+                        record.line,
+                        column,
+                        format!("{name} 只适用于 {owner}"),
+                    );
+                }
+            }
+        }
+
+        if let Some(kind) = kind {
+            list.spans.push(SourceSpan {
+                line: record.line,
+                column: id_column + 1,
+            });
+            list.items.push(ItemEntry {
+                id: id.to_string(),
+                name: text("name"),
+                atk,
+                def,
+                max_hp,
+                rng,
+                kind,
+            });
+        }
+    }
+
+    if issues.is_empty() {
+        Ok(list)
+    } else {
+        Err(report(issues))
+    }
+}
+
+fn push(issues: &mut Vec<ItemIssue>, item_id: &str, line: usize, column: usize, message: String) {
+    issues.push(ItemIssue {
+        item_id: item_id.to_string(),
+        span: Some(SourceSpan { line, column }),
+        message,
+    });
+}
+
+/// 按 [`CSV_COLUMNS`] 的列顺序导出
+pub fn write_items<'a>(items: impl IntoIterator<Item = &'a ItemEntry>) -> String {
+    let mut out = CSV_COLUMNS.join(",");
+    out.push('\n');
+    for item in items {
+        let (mut heal, mut hands, mut slot, mut effect, mut door_id) =
+            (String::new(), String::new(), "", String::new(), "");
+        match &item.kind {
+            ItemKind::Weapon { hands: h } => hands = h.to_string(),
+            ItemKind::Armor { slot: s } => slot = s.as_str(),
+            ItemKind::Potion { heal: h } => heal = h.to_string(),
+            ItemKind::Scroll { effect: e } => effect = ron::to_string(e).unwrap_or_default(),
+            ItemKind::Key { door_id: d } => door_id = d,
+            ItemKind::Misc => {}
+        }
+        let cells = [
+            item.id.as_str(),
+            item.name.as_str(),
+            &item.atk.to_string(),
+            &item.def.to_string(),
+            &item.max_hp.to_string(),
+            &item.rng.to_string(),
+            &heal,
+            kind_name(&item.kind),
+            &hands,
+            slot,
+            &effect,
+            door_id,
+        ]
+        .map(quote);
+        out.push_str(&cells.join(","));
+        out.push('\n');
+    }
+    out
+}
+
+fn kind_name(kind: &ItemKind) -> &'static str {
+    match kind {
+        ItemKind::Weapon { .. } => "weapon",
+        ItemKind::Armor { .. } => "armor",
+        ItemKind::Potion { .. } => "potion",
+        ItemKind::Scroll { .. } => "scroll",
+        ItemKind::Key { .. } => "key",
+        ItemKind::Misc => "misc",
+    }
+}
+
+fn parse_slot(raw: &str) -> Option<ArmorSlot> {
+    match raw.to_lowercase().as_str() {
+        "head" => Some(ArmorSlot::Head),
+        "body" => Some(ArmorSlot::Body),
+        "accessory" => Some(ArmorSlot::Accessory),
+        _ => None,
+    }
+}
+
+/// 含逗号、引号或换行的单元格加引号
+fn quote(cell: &str) -> String {
+    if cell.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", cell.replace('"', "\"\""))
+    } else {
+        cell.to_string()
+    }
+}
+
+/// 按 RFC 4180 切分记录：引号内可以有逗号、换行，`""` 表示一个引号。
+/// 引号未闭合时返回开引号的位置
+fn read_records(src: &str) -> Result<Vec<Record>, SourceSpan> {
+    let mut records = Vec::new();
+    let mut cells = Vec::new();
+    let mut cell = String::new();
+    let (mut line, mut column) = (1, 1);
+    let mut record_line = 1;
+    let mut quoted: Option<SourceSpan> = None;
+
+    let mut chars = src.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (quoted.is_some(), c) {
+            (true, '"') if chars.peek() == Some(&'"') => {
+                chars.next();
+                column += 1;
+                cell.push('"');
+            }
+            (true, '"') => quoted = None,
+            (true, '\n') => {
+                line += 1;
+                column = 0;
+                cell.push(c);
+            }
+            (true, _) => cell.push(c),
+            (false, '"') if cell.trim().is_empty() => {
+                cell.clear();
+                quoted = Some(SourceSpan { line, column });
+            }
+            (false, ',') => cells.push(std::mem::take(&mut cell)),
+            (false, '\r') if chars.peek() == Some(&'\n') => {}
+            (false, '\n') => {
+                cells.push(std::mem::take(&mut cell));
+                records.push(Record {
+                    line: record_line,
+                    cells: std::mem::take(&mut cells),
+                });
+                line += 1;
+                column = 0;
+                record_line = line;
+            }
+            (false, _) => cell.push(c),
+        }
+        column += 1;
+    }
+
+    if let Some(span) = quoted {
+        return Err(span);
+    }
+    if !cell.is_empty() || !cells.is_empty() {
+        cells.push(cell);
+        records.push(Record {
+            line: record_line,
+            cells,
+        });
+    }
+    Ok(records)
+}
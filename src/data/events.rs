@@ -0,0 +1,28 @@
+use bevy::prelude::*;
+
+use super::registry::ItemId;
+
+/// 请求重新加载物品数据（CLI `reload`；以 `--features file_watcher` 构建时文件变更会自动触发）
+#[derive(Event)]
+pub struct ReloadData;
+
+/// CLI `data import|export <csv>`：把 CSV 物品表导入当前会话，或把注册表导出为 CSV
+#[derive(Event, Debug, Clone, PartialEq)]
+pub enum ItemsCsv {
+    Import(String),
+    Export(String),
+}
+
+/// 物品数据已热重载并通过校验，注册表已更新；`changed` 为定义有变化的物品
+#[derive(Event)]
+pub struct ItemsReloaded {
+    pub changed: Vec<ItemId>,
+}
+
+/// 成长曲线已热重载并通过校验，各角色需按新曲线重算属性
+#[derive(Event)]
+pub struct ProgressionReloaded;
+
+/// 职业表已热重载并通过校验；已有角色保留基础属性，按新的职业成长重算属性
+#[derive(Event)]
+pub struct ClassesReloaded;
@@ -1,46 +1,300 @@
 use bevy::asset::{io::Reader, ron, AssetLoader, LoadContext};
-use bevy::prelude::*;
-use std::future::Future;
+use std::path::Path;
 use thiserror::Error;
 
-use super::schema::ItemList;
+use super::classes::Classes;
+use super::progression::Progression;
+use super::schema::{DataManifest, ItemList};
+use crate::core::hash::fnv1a_hex;
+use super::validate::{SourceSpan, ValidationReport};
 
+/// 物品表 Loader：RON / JSON / TOML / CSV 产出同一种 `ItemList`，按扩展名选择
 #[derive(Default)]
 pub struct RonItemLoader;
 
+#[derive(Default)]
+pub struct JsonItemLoader;
+
+#[derive(Default)]
+pub struct TomlItemLoader;
+
+#[derive(Default)]
+pub struct CsvItemLoader;
+
+/// 数据清单 Loader（与物品表共用 `.ron` 扩展名，按资产类型区分）
+#[derive(Default)]
+pub struct RonManifestLoader;
+
+/// 成长曲线 Loader（同样按资产类型区分）
+#[derive(Default)]
+pub struct RonProgressionLoader;
+
+/// 职业表 Loader（同样按资产类型区分）
+#[derive(Default)]
+pub struct RonClassesLoader;
+
 #[derive(Debug, Error)]
-pub enum RonItemLoaderError {
+pub enum ItemLoaderError {
     #[error("Could not load asset: {0}")]
     Io(#[from] std::io::Error),
     #[error("Could not parse RON: {0}")]
     Ron(#[from] ron::error::SpannedError),
+    #[error("Could not parse JSON: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Could not parse TOML: {0}")]
+    Toml(#[from] toml::de::Error),
+    #[error("Could not parse CSV: {0}")]
+    Csv(ValidationReport),
     #[error("Could not interpret bytes as UTF-8: {0}")]
     Utf8(#[from] std::str::Utf8Error),
 }
 
+/// 物品表文件格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ItemFormat {
+    Ron,
+    Json,
+    Toml,
+    Csv,
+}
+
+impl ItemFormat {
+    /// 按扩展名判断格式（忽略大小写）
+    pub fn from_path(path: impl AsRef<Path>) -> Option<Self> {
+        let ext = path.as_ref().extension()?.to_str()?.to_ascii_lowercase();
+        match ext.as_str() {
+            "ron" => Some(ItemFormat::Ron),
+            "json" => Some(ItemFormat::Json),
+            "toml" => Some(ItemFormat::Toml),
+            "csv" => Some(ItemFormat::Csv),
+            _ => None,
+        }
+    }
+
+    /// 条目 id 字段的键值分隔符与行注释
+    fn syntax(self) -> (char, Option<&'static str>) {
+        match self {
+            ItemFormat::Ron => (':', Some("//")),
+            ItemFormat::Json => (':', None),
+            ItemFormat::Toml => ('=', Some("#")),
+            ItemFormat::Csv => unreachable!("CSV 按行列定位"),
+        }
+    }
+}
+
+async fn load_items(
+    reader: &mut dyn Reader,
+    load_context: &LoadContext<'_>,
+    format: ItemFormat,
+) -> Result<ItemList, ItemLoaderError> {
+    let mut bytes = Vec::new();
+    reader.read_to_end(&mut bytes).await?;
+
+    let s = std::str::from_utf8(&bytes)?;
+    let mut list = parse_items_as(format, s, &load_context.path().display().to_string())?;
+    list.hash = fnv1a_hex(&bytes);
+    Ok(list)
+}
+
 impl AssetLoader for RonItemLoader {
     type Asset = ItemList;
     type Settings = ();
-    type Error = RonItemLoaderError;
+    type Error = ItemLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &Self::Settings,
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        load_items(reader, load_context, ItemFormat::Ron).await
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["ron"]
+    }
+}
+
+impl AssetLoader for JsonItemLoader {
+    type Asset = ItemList;
+    type Settings = ();
+    type Error = ItemLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &Self::Settings,
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        load_items(reader, load_context, ItemFormat::Json).await
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["json"]
+    }
+}
+
+impl AssetLoader for TomlItemLoader {
+    type Asset = ItemList;
+    type Settings = ();
+    type Error = ItemLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &Self::Settings,
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        load_items(reader, load_context, ItemFormat::Toml).await
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["toml"]
+    }
+}
+
+impl AssetLoader for CsvItemLoader {
+    type Asset = ItemList;
+    type Settings = ();
+    type Error = ItemLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &Self::Settings,
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        load_items(reader, load_context, ItemFormat::Csv).await
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["csv"]
+    }
+}
+
+impl AssetLoader for RonManifestLoader {
+    type Asset = DataManifest;
+    type Settings = ();
+    type Error = ItemLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &Self::Settings,
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+
+        let s = std::str::from_utf8(&bytes)?;
+        Ok(ron::de::from_str(s)?)
+    }
+}
+
+impl AssetLoader for RonProgressionLoader {
+    type Asset = Progression;
+    type Settings = ();
+    type Error = ItemLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &Self::Settings,
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+
+        let s = std::str::from_utf8(&bytes)?;
+        Ok(ron::de::from_str(s)?)
+    }
+}
+
+impl AssetLoader for RonClassesLoader {
+    type Asset = Classes;
+    type Settings = ();
+    type Error = ItemLoaderError;
 
-    // --- 正确的签名 ---
-    // 1. 移除 fn load<'a> 中的 <'a>
-    // 2. 移除所有参数前的 'a
-    // 3. 移除返回值 impl Future... 末尾的 + 'a
-    fn load(
+    async fn load(
         &self,
         reader: &mut dyn Reader,
         _settings: &Self::Settings,
-        _load_context: &mut LoadContext,
-    ) -> impl Future<Output = Result<Self::Asset, Self::Error>> + Send {
-        async move {
-            let mut bytes = Vec::new();
-            reader.read_to_end(&mut bytes).await?;
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+
+        let s = std::str::from_utf8(&bytes)?;
+        Ok(ron::de::from_str(s)?)
+    }
+}
+
+/// 解析物品表，格式按 `source` 的扩展名选择（无法识别时按 RON）
+pub fn parse_items(src: &str, source: &str) -> Result<ItemList, ItemLoaderError> {
+    let format = ItemFormat::from_path(source).unwrap_or(ItemFormat::Ron);
+    parse_items_as(format, src, source)
+}
 
-            let s = std::str::from_utf8(&bytes)?;
-            let list: ItemList = ron::de::from_str(s)?;
+/// 按指定格式解析物品表，并记录每个条目的源文件位置
+pub fn parse_items_as(
+    format: ItemFormat,
+    src: &str,
+    source: &str,
+) -> Result<ItemList, ItemLoaderError> {
+    let mut list: ItemList = match format {
+        ItemFormat::Ron => ron::de::from_str(src)?,
+        ItemFormat::Json => serde_json::from_str(src)?,
+        ItemFormat::Toml => toml::from_str(src)?,
+        // CSV 按行列解析，自带位置
+        ItemFormat::Csv => {
+            return super::csv::parse_items(src, source).map_err(ItemLoaderError::Csv);
+        }
+    };
+    list.source = source.to_string();
+
+    // 每个条目恰好有一个 id 字段，按出现顺序对应；对不上时放弃定位
+    let spans = locate_id_fields(src, format);
+    if spans.len() == list.items.len() {
+        list.spans = spans;
+    }
+    Ok(list)
+}
+
+/// 找出所有 id 字段的位置：RON `id:`、JSON `"id":`、TOML `id =`
+/// （跳过 `door_id` 之类的后缀匹配与注释）
+fn locate_id_fields(src: &str, format: ItemFormat) -> Vec<SourceSpan> {
+    let (separator, comment) = format.syntax();
+    let mut spans = Vec::new();
+    for (line_idx, line) in src.lines().enumerate() {
+        let code = match comment {
+            Some(comment) => line.split(comment).next().unwrap_or_default(),
+            None => line,
+        };
+        let mut from = 0;
+        while let Some(pos) = code[from..].find("id") {
+            let start = from + pos;
+            from = start + 2;
 
-            Ok(list)
+            let prev = code[..start].chars().next_back();
+            if prev.is_some_and(|c| c.is_alphanumeric() || c == '_') {
+                continue;
+            }
+            // JSON 的键带引号
+            let rest = &code[from..];
+            let rest = match prev {
+                Some('"') => match rest.strip_prefix('"') {
+                    Some(rest) => rest,
+                    None => continue,
+                },
+                _ => rest,
+            };
+            if rest.trim_start().starts_with(separator) {
+                spans.push(SourceSpan {
+                    line: line_idx + 1,
+                    column: code[..start].chars().count() + 1,
+                });
+            }
         }
     }
-}
\ No newline at end of file
+    spans
+}
@@ -0,0 +1,64 @@
+//! 多个物品文件按清单顺序合并为一张物品表
+//!
+//! 规则：后面的文件覆盖前面文件中同 id（忽略大小写）的条目，且保留被覆盖条目的位置；
+//! 新 id 依次追加到末尾。因此合并结果只取决于清单顺序。
+
+use std::collections::HashMap;
+
+use super::schema::ItemList;
+
+/// 一次按 id 覆盖
+#[derive(Debug, Clone, PartialEq)]
+pub struct ItemOverride {
+    pub id: String,
+    /// 原条目所在文件
+    pub base: String,
+    /// 覆盖它的文件
+    pub by: String,
+}
+
+/// 合并结果
+#[derive(Debug)]
+pub struct MergedItems {
+    pub list: ItemList,
+    pub overrides: Vec<ItemOverride>,
+}
+
+/// 按顺序合并；`source` 为合并后物品表的来源描述
+pub fn merge_item_lists(source: &str, lists: &[&ItemList]) -> MergedItems {
+    let mut items = Vec::new();
+    let mut origins: Vec<&str> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+    let mut overrides = Vec::new();
+
+    for list in lists {
+        for entry in &list.items {
+            match index.get(&entry.id.to_lowercase()) {
+                Some(&idx) => {
+                    overrides.push(ItemOverride {
+                        id: entry.id.clone(),
+                        base: origins[idx].to_string(),
+                        by: list.source.clone(),
+                    });
+                    items[idx] = entry.clone();
+                    origins[idx] = &list.source;
+                }
+                None => {
+                    index.insert(entry.id.to_lowercase(), items.len());
+                    items.push(entry.clone());
+                    origins.push(&list.source);
+                }
+            }
+        }
+    }
+
+    MergedItems {
+        list: ItemList {
+            items,
+            source: source.to_string(),
+            spans: Vec::new(),
+            hash: String::new(),
+        },
+        overrides,
+    }
+}
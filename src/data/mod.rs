@@ -1,16 +1,88 @@
+pub mod classes;
+pub mod commands;
+pub mod csv;
+pub mod events;
 pub mod schema;
 pub mod loader;
+pub mod merge;
+pub mod progression;
+pub mod registry;
+pub mod validate;
 
+use bevy::asset::{LoadState, UntypedAssetId};
 use bevy::prelude::*;
-use schema::ItemList;
-use crate::core::states::AppState;
+use classes::Classes;
+use events::*;
+use progression::Progression;
+use registry::ItemRegistry;
+use schema::{DataManifest, ItemList};
+use std::time::Duration;
+use crate::core::{
+    args::LaunchArgs, events::LogEvent, resources::GameConfig, states::AppState,
+};
+use crate::interface::command::CliAppExt;
+
+/// 数据加载失败时的退出码（批处理的 0/1/2 之外）
+pub const LOAD_FAILED_EXIT_CODE: u8 = 3;
+
+/// 数据清单路径
+pub const MANIFEST_PATH: &str = "data/manifest.ron";
 
 // --------------------------- 资源 ---------------------------
 #[derive(Resource, Default)]
 pub struct ItemAssets {
-    pub(crate) handle: Option<Handle<ItemList>>,
+    manifest: Option<Handle<DataManifest>>,
+    /// 清单列出的物品文件，按覆盖顺序
+    sources: Vec<(String, Handle<ItemList>)>,
+    /// 清单指定的成长曲线文件
+    progression: Option<(String, Handle<Progression>)>,
+    /// 清单指定的职业文件
+    classes: Option<(String, Handle<Classes>)>,
 }
 
+impl ItemAssets {
+    /// 已加载物品文件的路径与内容哈希，按清单顺序
+    pub fn file_hashes(&self, lists: &Assets<ItemList>) -> Vec<(String, String)> {
+        self.sources
+            .iter()
+            .filter_map(|(path, handle)| Some((path.clone(), lists.get(handle)?.hash.clone())))
+            .collect()
+    }
+}
+
+/// 进入 InGame 前必须加载完成的数据文件
+#[derive(Resource, Default)]
+pub struct DataLoading {
+    handles: Vec<(String, UntypedHandle)>,
+    started: Option<Duration>,
+    /// `reload` 已发出、尚未收到结果的文件（批处理据此等待）
+    reloading: Vec<UntypedAssetId>,
+    /// 有物品文件已更新，等其余文件重新加载完再合并
+    reload_dirty: bool,
+}
+
+impl DataLoading {
+    /// 登记一个需要等待的数据文件
+    pub fn track(&mut self, path: impl Into<String>, handle: impl Into<UntypedHandle>) {
+        self.handles.push((path.into(), handle.into()));
+    }
+
+    pub fn is_reloading(&self) -> bool {
+        !self.reloading.is_empty()
+    }
+
+    fn path_of(&self, id: UntypedAssetId) -> &str {
+        self.handles
+            .iter()
+            .find(|(_, handle)| handle.id() == id)
+            .map_or("?", |(path, _)| path.as_str())
+    }
+}
+
+/// 数据加载失败的原因，进入 `AppState::LoadFailed` 时展示
+#[derive(Resource, Debug, Clone)]
+pub struct LoadFailure(pub String);
+
 // --------------------------- 插件 ---------------------------
 pub struct DataPlugin;
 impl Plugin for DataPlugin {
@@ -18,14 +90,47 @@ impl Plugin for DataPlugin {
         app
             // 注册资产类型 & Loader
             .init_asset::<ItemList>()
-            .register_asset_loader(loader::RonItemLoader::default())
+            .init_asset::<DataManifest>()
+            .init_asset::<Progression>()
+            .init_asset::<Classes>()
+            .register_asset_loader(loader::RonItemLoader)
+            .register_asset_loader(loader::JsonItemLoader)
+            .register_asset_loader(loader::TomlItemLoader)
+            .register_asset_loader(loader::CsvItemLoader)
+            .register_asset_loader(loader::RonManifestLoader)
+            .register_asset_loader(loader::RonProgressionLoader)
+            .register_asset_loader(loader::RonClassesLoader)
             // 注册资源
             .init_resource::<ItemAssets>()
-            // Loading 流程
+            .init_resource::<ItemRegistry>()
+            .init_resource::<Progression>()
+            .init_resource::<Classes>()
+            .init_resource::<DataLoading>()
+            .init_resource::<GameConfig>()
+            // Loading 流程：清单 → 物品文件 → 校验 → 合并 → CharacterSelect / LoadFailed
             .add_systems(OnEnter(AppState::Loading), start_loading)
             .add_systems(
                 Update,
                 check_loaded.run_if(in_state(AppState::Loading)),
+            )
+            .add_systems(OnEnter(AppState::LoadFailed), show_load_failure)
+            // 热重载：CLI `reload` 或 `file_watcher` feature 监视到文件变更时触发
+            .register_cli_command::<ReloadData>()
+            .register_cli_command::<ItemsCsv>()
+            .add_event::<ItemsReloaded>()
+            .add_event::<ProgressionReloaded>()
+            .add_event::<ClassesReloaded>()
+            .add_systems(
+                Update,
+                (
+                    request_reload,
+                    apply_item_reload,
+                    apply_progression_reload,
+                    apply_classes_reload,
+                    import_export_csv,
+                )
+                    .chain()
+                    .run_if(in_state(AppState::InGame)),
             );
     }
 }
@@ -33,21 +138,403 @@ impl Plugin for DataPlugin {
 // --------------------------- 系统 ---------------------------
 fn start_loading(
     mut item_assets: ResMut<ItemAssets>,
+    mut loading: ResMut<DataLoading>,
     asset_server: Res<AssetServer>,
 ) {
-    let handle: Handle<ItemList> = asset_server.load("data/items.ron");
-    item_assets.handle = Some(handle);
+    let handle: Handle<DataManifest> = asset_server.load(MANIFEST_PATH);
+    loading.track(MANIFEST_PATH, handle.clone());
+    item_assets.manifest = Some(handle);
 }
 
+/// 等待所有登记的数据文件：任何一个加载失败或超时都进入 LoadFailed。
+/// 清单加载后再请求它列出的物品文件、成长曲线与职业；全部加载后逐个校验并按清单顺序合并，
+/// 数据有问题同样进入 LoadFailed，通过后由合并结果构建 `ItemRegistry`，
+/// 成长曲线与职业（引用的物品需在注册表中）校验通过后插入 `Progression` / `Classes`。
+/// `--validate-data` 模式下校验通过即退出，否则进入职业选择
+#[allow(clippy::too_many_arguments)]
 fn check_loaded(
+    mut commands: Commands,
     mut next: ResMut<NextState<AppState>>,
+    mut app_exit: EventWriter<AppExit>,
+    mut loading: ResMut<DataLoading>,
+    mut item_assets: ResMut<ItemAssets>,
+    lists: Res<Assets<ItemList>>,
+    manifests: Res<Assets<DataManifest>>,
+    progressions: Res<Assets<Progression>>,
+    class_lists: Res<Assets<Classes>>,
+    asset_server: Res<AssetServer>,
+    config: Res<GameConfig>,
+    time: Res<Time<Real>>,
+    args: Option<Res<LaunchArgs>>,
+) {
+    let mut fail = |reason: String| {
+        commands.insert_resource(LoadFailure(reason));
+        next.set(AppState::LoadFailed);
+    };
+
+    let mut errors = Vec::new();
+    let mut pending = Vec::new();
+    for (path, handle) in &loading.handles {
+        match asset_server.load_state(handle.id()) {
+            LoadState::Loaded => {}
+            LoadState::Failed(err) => errors.push(format!("{path}: {err}")),
+            LoadState::NotLoaded | LoadState::Loading => pending.push(path.clone()),
+        }
+    }
+    if !errors.is_empty() {
+        fail(errors.join("\n"));
+        return;
+    }
+    if !pending.is_empty() {
+        let started = *loading.started.get_or_insert(time.elapsed());
+        if time.elapsed() - started > config.load_timeout {
+            fail(format!(
+                "加载超时（{}s），仍在等待: {}",
+                config.load_timeout.as_secs_f32(),
+                pending.join(", ")
+            ));
+        }
+        return;
+    }
+
+    // 清单已就绪：请求它列出的物品文件，下一轮继续等待
+    if item_assets.sources.is_empty() {
+        let Some(manifest) = item_assets.manifest.as_ref().and_then(|h| manifests.get(h)) else {
+            return;
+        };
+        if manifest.items.is_empty() {
+            fail(format!("{MANIFEST_PATH}: 没有列出任何物品文件"));
+            return;
+        }
+        for path in &manifest.items {
+            let handle: Handle<ItemList> = asset_server.load(path);
+            loading.track(path.clone(), handle.clone());
+            item_assets.sources.push((path.clone(), handle));
+        }
+        if let Some(path) = &manifest.progression {
+            let handle: Handle<Progression> = asset_server.load(path);
+            loading.track(path.clone(), handle.clone());
+            item_assets.progression = Some((path.clone(), handle));
+        }
+        if let Some(path) = &manifest.classes {
+            let handle: Handle<Classes> = asset_server.load(path);
+            loading.track(path.clone(), handle.clone());
+            item_assets.classes = Some((path.clone(), handle));
+        }
+        return;
+    }
+
+    let merged = match merge_sources(&item_assets, &lists) {
+        Ok(merged) => merged,
+        Err(reason) => {
+            fail(reason);
+            return;
+        }
+    };
+
+    for o in &merged.overrides {
+        info!("物品 {} 被 {} 覆盖（原定义于 {}）", o.id, o.by, o.base);
+    }
+    println!("✔ Items loaded: {}", merged.list.items.len());
+    let registry = ItemRegistry::from_list(&merged.list);
+
+    let progression = item_assets
+        .progression
+        .as_ref()
+        .and_then(|(path, handle)| Some((path, progressions.get(handle)?)));
+    if let Some((path, progression)) = progression {
+        let problems = progression.validate(&registry);
+        if !problems.is_empty() {
+            fail(format!("{path}: 成长曲线校验失败\n  {}", problems.join("\n  ")));
+            return;
+        }
+    }
+    let classes = item_assets
+        .classes
+        .as_ref()
+        .and_then(|(path, handle)| Some((path, class_lists.get(handle)?)));
+    if let Some((path, classes)) = classes {
+        let problems = classes.validate(&registry);
+        if !problems.is_empty() {
+            fail(format!("{path}: 职业校验失败\n  {}", problems.join("\n  ")));
+            return;
+        }
+    }
+    if let Some((_, progression)) = progression {
+        commands.insert_resource(progression.clone());
+    }
+    if let Some((_, classes)) = classes {
+        commands.insert_resource(classes.clone());
+    }
+    commands.insert_resource(registry);
+
+    if args.is_some_and(|args| args.validate_data) {
+        app_exit.write(AppExit::Success);
+    } else {
+        next.set(AppState::CharacterSelect);
+    }
+}
+
+/// 逐个校验物品文件，全部通过后按清单顺序合并，再校验合并结果（不同文件间的重名）
+fn merge_sources(
+    item_assets: &ItemAssets,
+    lists: &Assets<ItemList>,
+) -> Result<merge::MergedItems, String> {
+    let sources: Vec<&ItemList> = item_assets
+        .sources
+        .iter()
+        .filter_map(|(_, handle)| lists.get(handle))
+        .collect();
+
+    let reports: Vec<String> = sources
+        .iter()
+        .map(|list| validate::validate(list))
+        .filter(|report| !report.is_ok())
+        .map(|report| report.to_string())
+        .collect();
+    if !reports.is_empty() {
+        return Err(format!("物品数据校验失败\n{}", reports.join("\n")));
+    }
+
+    let merged = merge::merge_item_lists(MANIFEST_PATH, &sources);
+    let report = validate::validate(&merged.list);
+    if !report.is_ok() {
+        return Err(format!("物品数据校验失败\n{report}"));
+    }
+    Ok(merged)
+}
+
+/// 错误页：打印原因并以非零退出码结束
+fn show_load_failure(failure: Res<LoadFailure>, mut app_exit: EventWriter<AppExit>) {
+    let rule = "=".repeat(50);
+    eprintln!("{rule}\n✘ 数据加载失败，无法进入游戏\n{rule}\n{}\n{rule}", failure.0);
+    app_exit.write(AppExit::from_code(LOAD_FAILED_EXIT_CODE));
+}
+
+/// 处理 `reload`：让 AssetServer 重新读取清单与所有物品文件
+fn request_reload(
+    mut ev_reload: EventReader<ReloadData>,
+    mut loading: ResMut<DataLoading>,
+    asset_server: Res<AssetServer>,
+) {
+    if ev_reload.read().count() == 0 {
+        return;
+    }
+    for (path, _) in &loading.handles {
+        asset_server.reload(path.clone());
+    }
+    loading.reloading = loading.handles.iter().map(|(_, h)| h.id()).collect();
+}
+
+/// 物品文件被替换后重新校验并合并，通过才更新注册表并通知各模块；
+/// 语法错误时 AssetServer 保留旧数据。清单本身的变更需要重启才生效
+#[allow(clippy::too_many_arguments)]
+fn apply_item_reload(
+    mut item_events: EventReader<AssetEvent<ItemList>>,
+    mut manifest_events: EventReader<AssetEvent<DataManifest>>,
+    mut ev_reloaded: EventWriter<ItemsReloaded>,
+    mut log_event: EventWriter<LogEvent>,
+    mut loading: ResMut<DataLoading>,
     item_assets: Res<ItemAssets>,
+    mut registry: ResMut<ItemRegistry>,
     lists: Res<Assets<ItemList>>,
+    manifests: Res<Assets<DataManifest>>,
+    asset_server: Res<AssetServer>,
 ) {
-    if let Some(h) = &item_assets.handle {
-        if let Some(list) = lists.get(h) {
-            println!("✔ Items loaded: {}", list.items.len());
-            next.set(AppState::InGame);
+    let Some(manifest_handle) = &item_assets.manifest else {
+        return;
+    };
+
+    for ev in item_events.read() {
+        if let AssetEvent::Modified { id } = ev {
+            let id = id.untyped();
+            loading.reloading.retain(|pending| *pending != id);
+            if item_assets.sources.iter().any(|(_, h)| h.id().untyped() == id) {
+                loading.reload_dirty = true;
+            }
         }
     }
-}
\ No newline at end of file
+    for ev in manifest_events.read() {
+        if ev.is_modified(manifest_handle.id()) {
+            loading.reloading.retain(|pending| *pending != manifest_handle.id().untyped());
+            let listed = manifests.get(manifest_handle).map(|m| m.items.as_slice());
+            let current: Vec<String> = item_assets.sources.iter().map(|(p, _)| p.clone()).collect();
+            if listed.is_some_and(|listed| listed != current.as_slice()) {
+                log_event.write(LogEvent(format!("{MANIFEST_PATH} 已变更，重启后生效")));
+            }
+        }
+    }
+
+    let failed: Vec<_> = loading
+        .reloading
+        .iter()
+        .copied()
+        .filter(|id| asset_server.load_state(*id).is_failed())
+        .collect();
+    for id in failed {
+        loading.reloading.retain(|pending| *pending != id);
+        log_event.write(LogEvent(format!(
+            "✘ {} 重新加载失败，继续使用旧数据",
+            loading.path_of(id)
+        )));
+    }
+
+    if !loading.reload_dirty || loading.is_reloading() {
+        return;
+    }
+    loading.reload_dirty = false;
+
+    match merge_sources(&item_assets, &lists) {
+        Ok(merged) => {
+            log_event.write(LogEvent(format!(
+                "✔ Items reloaded: {}",
+                merged.list.items.len()
+            )));
+            let changed = registry.reload(&merged.list);
+            ev_reloaded.write(ItemsReloaded { changed });
+        }
+        Err(reason) => {
+            log_event.write(LogEvent(format!(
+                "✘ 重新加载的物品数据有问题，已有物品保持不变\n{reason}"
+            )));
+        }
+    }
+}
+
+/// 成长曲线文件被替换后重新校验，通过且有变化才替换 `Progression` 并通知各角色重算属性
+#[allow(clippy::too_many_arguments)]
+fn apply_progression_reload(
+    mut events: EventReader<AssetEvent<Progression>>,
+    mut ev_reloaded: EventWriter<ProgressionReloaded>,
+    mut log_event: EventWriter<LogEvent>,
+    mut loading: ResMut<DataLoading>,
+    item_assets: Res<ItemAssets>,
+    registry: Res<ItemRegistry>,
+    progressions: Res<Assets<Progression>>,
+    mut progression: ResMut<Progression>,
+) {
+    let Some((path, handle)) = &item_assets.progression else {
+        return;
+    };
+    if !events.read().any(|ev| ev.is_modified(handle.id())) {
+        return;
+    }
+    loading.reloading.retain(|pending| *pending != handle.id().untyped());
+    let Some(reloaded) = progressions.get(handle) else {
+        return;
+    };
+
+    let problems = reloaded.validate(&registry);
+    if !problems.is_empty() {
+        log_event.write(LogEvent(format!(
+            "✘ 重新加载的成长曲线有问题，继续使用旧曲线\n{path}: 成长曲线校验失败\n  {}",
+            problems.join("\n  ")
+        )));
+        return;
+    }
+    // 内容没变（如 `reload` 重新读取了所有文件）时不提示
+    if *progression != *reloaded {
+        *progression = reloaded.clone();
+        ev_reloaded.write(ProgressionReloaded);
+        log_event.write(LogEvent(format!("✔ 成长曲线已重新加载: {path}")));
+    }
+}
+
+/// 职业文件被替换后重新校验，通过且有变化才替换 `Classes`；开局属性只影响之后的新游戏，
+/// 成长与可用装备对已有角色立即生效
+#[allow(clippy::too_many_arguments)]
+fn apply_classes_reload(
+    mut events: EventReader<AssetEvent<Classes>>,
+    mut ev_reloaded: EventWriter<ClassesReloaded>,
+    mut log_event: EventWriter<LogEvent>,
+    mut loading: ResMut<DataLoading>,
+    item_assets: Res<ItemAssets>,
+    registry: Res<ItemRegistry>,
+    class_lists: Res<Assets<Classes>>,
+    mut classes: ResMut<Classes>,
+) {
+    let Some((path, handle)) = &item_assets.classes else {
+        return;
+    };
+    if !events.read().any(|ev| ev.is_modified(handle.id())) {
+        return;
+    }
+    loading.reloading.retain(|pending| *pending != handle.id().untyped());
+    let Some(reloaded) = class_lists.get(handle) else {
+        return;
+    };
+
+    let problems = reloaded.validate(&registry);
+    if !problems.is_empty() {
+        log_event.write(LogEvent(format!(
+            "✘ 重新加载的职业有问题，继续使用旧职业\n{path}: 职业校验失败\n  {}",
+            problems.join("\n  ")
+        )));
+        return;
+    }
+    if *classes != *reloaded {
+        *classes = reloaded.clone();
+        ev_reloaded.write(ClassesReloaded);
+        log_event.write(LogEvent(format!("✔ 职业已重新加载: {path}")));
+    }
+}
+
+/// `data import <csv>`：校验后按 id 合并进注册表（只作用于当前会话，下次 `reload`
+/// 以清单为准）；`data export <csv>`：把注册表写成 CSV
+fn import_export_csv(
+    mut ev_csv: EventReader<ItemsCsv>,
+    mut ev_reloaded: EventWriter<ItemsReloaded>,
+    mut log_event: EventWriter<LogEvent>,
+    mut registry: ResMut<ItemRegistry>,
+) {
+    for ev in ev_csv.read() {
+        match ev {
+            ItemsCsv::Import(path) => {
+                let imported = std::fs::read_to_string(path)
+                    .map_err(|err| format!("{path}: {err}"))
+                    .and_then(|src| csv::parse_items(&src, path).map_err(|r| r.to_string()))
+                    .and_then(|list| {
+                        let report = validate::validate(&list);
+                        if report.is_ok() { Ok(list) } else { Err(report.to_string()) }
+                    });
+                let imported = match imported {
+                    Ok(list) => list,
+                    Err(reason) => {
+                        log_event.write(LogEvent(format!("✘ 导入失败，物品保持不变\n{reason}")));
+                        continue;
+                    }
+                };
+
+                let current = ItemList {
+                    items: registry.iter().map(|(_, entry)| entry.clone()).collect(),
+                    source: "registry".into(),
+                    spans: Vec::new(),
+                    hash: String::new(),
+                };
+                let merged = merge::merge_item_lists(path, &[&current, &imported]);
+                let report = validate::validate(&merged.list);
+                if !report.is_ok() {
+                    log_event.write(LogEvent(format!("✘ 导入失败，物品保持不变\n{report}")));
+                    continue;
+                }
+                let added = merged.list.items.len() - current.items.len();
+                let changed = registry.reload(&merged.list);
+                log_event.write(LogEvent(format!(
+                    "✔ 已导入 {path}: 新增 {added}，覆盖 {}",
+                    merged.overrides.len()
+                )));
+                ev_reloaded.write(ItemsReloaded { changed });
+            }
+            ItemsCsv::Export(path) => {
+                let items = registry.iter().map(|(_, entry)| entry);
+                match std::fs::write(path, csv::write_items(items)) {
+                    Ok(()) => log_event.write(LogEvent(format!(
+                        "✔ 已导出 {} 个物品到 {path}",
+                        registry.len()
+                    ))),
+                    Err(err) => log_event.write(LogEvent(format!("✘ 导出失败 {path}: {err}"))),
+                };
+            }
+        }
+    }
+}
@@ -0,0 +1,205 @@
+//! 成长曲线（`data/progression.ron`）：升级所需经验、每级属性成长、等级上限与等级奖励
+//!
+//! 由清单的 `progression` 字段指定，随物品数据一起加载与热重载；清单未指定时使用
+//! [`Progression::default`]（10 × lv² 经验，每级 +2 生命上限、+1 攻击、+1 防御）。
+//!
+//! ```text
+//! (
+//!   level_cap: 20,
+//!   exp: Formula(base: 10, exponent: 2),
+//!   growth: (max_hp: 2, atk: 1, def: 1),
+//!   rewards: [(level: 5, growth: (max_hp: 5, atk: 2), items: [("potion_heal_small", 2)], skill_points: 1)],
+//! )
+//! ```
+
+use bevy::asset::Asset;
+use bevy::prelude::Resource;
+use bevy::reflect::TypePath;
+use serde::Deserialize;
+use std::collections::HashSet;
+
+use super::registry::ItemRegistry;
+use super::schema::implicit_some;
+
+/// 成长曲线：既是资产（从文件加载），也是资源（加载并校验后插入）
+#[derive(Asset, TypePath, Resource, Deserialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Progression {
+    /// 等级上限：达到后不再升级
+    pub level_cap: i32,
+    /// 升级所需经验
+    pub exp: ExpCurve,
+    /// 每升一级的属性成长
+    pub growth: StatGrowth,
+    /// 特定等级的成长与奖励
+    #[serde(default)]
+    pub rewards: Vec<LevelReward>,
+}
+
+impl Default for Progression {
+    fn default() -> Self {
+        Self {
+            level_cap: 99,
+            exp: ExpCurve::Formula {
+                base: 10,
+                exponent: 2,
+            },
+            growth: StatGrowth {
+                max_hp: 2,
+                atk: 1,
+                def: 1,
+                rng: 0,
+            },
+            rewards: Vec::new(),
+        }
+    }
+}
+
+/// 从 lv 级升到 lv + 1 级所需经验
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub enum ExpCurve {
+    /// base × lv^exponent
+    Formula { base: i32, exponent: u32 },
+    /// 第 i 项为从 i + 1 级升级所需经验；超出表长时沿用最后一项
+    Table(Vec<i32>),
+}
+
+/// 一次升级带来的属性成长
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct StatGrowth {
+    #[serde(default)]
+    pub max_hp: i32,
+    #[serde(default)]
+    pub atk: i32,
+    #[serde(default)]
+    pub def: i32,
+    #[serde(default)]
+    pub rng: i32,
+}
+
+impl StatGrowth {
+    fn add(&mut self, other: &StatGrowth) {
+        self.max_hp += other.max_hp;
+        self.atk += other.atk;
+        self.def += other.def;
+        self.rng += other.rng;
+    }
+
+    /// 非零的各项：`生命值 +2，攻击力 +1，防御力 +1`
+    pub fn describe(&self) -> String {
+        [
+            ("生命值", self.max_hp),
+            ("攻击力", self.atk),
+            ("防御力", self.def),
+            ("攻击距离", self.rng),
+        ]
+        .into_iter()
+        .filter(|(_, value)| *value != 0)
+        .map(|(label, value)| format!("{label} {value:+}"))
+        .collect::<Vec<_>>()
+        .join("，")
+    }
+}
+
+/// 升到 `level` 级时：替代默认成长、奖励物品与技能点
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct LevelReward {
+    pub level: i32,
+    #[serde(default, deserialize_with = "implicit_some")]
+    pub growth: Option<StatGrowth>,
+    /// (物品 id, 数量)
+    #[serde(default)]
+    pub items: Vec<(String, u32)>,
+    #[serde(default)]
+    pub skill_points: u32,
+}
+
+impl Progression {
+    /// 从 lv 级升级所需经验；已达等级上限时为 `None`
+    pub fn exp_to_next(&self, lv: i32) -> Option<i32> {
+        if lv >= self.level_cap {
+            return None;
+        }
+        let lv = lv.max(1);
+        Some(match &self.exp {
+            ExpCurve::Formula { base, exponent } => {
+                base.saturating_mul(lv.saturating_pow(*exponent))
+            }
+            ExpCurve::Table(table) => {
+                let idx = (lv as usize - 1).min(table.len().saturating_sub(1));
+                table.get(idx).copied().unwrap_or(i32::MAX)
+            }
+        })
+    }
+
+    /// 升到 lv 级时的奖励
+    pub fn reward(&self, lv: i32) -> Option<&LevelReward> {
+        self.rewards.iter().find(|reward| reward.level == lv)
+    }
+
+    /// 升到 lv 级时的属性成长
+    pub fn growth_at(&self, lv: i32) -> StatGrowth {
+        self.reward(lv)
+            .and_then(|reward| reward.growth)
+            .unwrap_or(self.growth)
+    }
+
+    /// 从 `from` 级升到 `to` 级的累计成长
+    pub fn growth_between(&self, from: i32, to: i32) -> StatGrowth {
+        let mut total = StatGrowth::default();
+        for level in (from + 1).max(2)..=to.min(self.level_cap) {
+            total.add(&self.growth_at(level));
+        }
+        total
+    }
+
+    /// 从 1 级升到 lv 级的累计成长
+    pub fn total_growth(&self, lv: i32) -> StatGrowth {
+        self.growth_between(1, lv)
+    }
+
+    /// 反序列化无法表达的问题；`registry` 用于检查奖励物品是否存在
+    pub fn validate(&self, registry: &ItemRegistry) -> Vec<String> {
+        let mut problems = Vec::new();
+        if self.level_cap < 1 {
+            problems.push(format!("level_cap 至少为 1，得到 {}", self.level_cap));
+        }
+        match &self.exp {
+            ExpCurve::Formula { base, .. } if *base <= 0 => {
+                problems.push(format!("exp 的 base 应为正数，得到 {base}"));
+            }
+            ExpCurve::Table(table) if table.is_empty() => {
+                problems.push("exp 的经验表为空".into());
+            }
+            ExpCurve::Table(table) => {
+                if let Some((idx, exp)) = table.iter().enumerate().find(|(_, exp)| **exp <= 0) {
+                    problems.push(format!("exp 的经验表第 {} 项应为正数，得到 {exp}", idx + 1));
+                }
+            }
+            ExpCurve::Formula { .. } => {}
+        }
+
+        let mut seen = HashSet::new();
+        for reward in &self.rewards {
+            let level = reward.level;
+            if !(2..=self.level_cap).contains(&level) {
+                problems.push(format!("rewards 的等级 {level} 超出范围 2..={}", self.level_cap));
+            }
+            if !seen.insert(level) {
+                problems.push(format!("rewards 的等级 {level} 重复"));
+            }
+            for (id, count) in &reward.items {
+                if registry.by_id(id).is_none() {
+                    problems.push(format!("等级 {level} 的奖励物品 {id} 不存在"));
+                }
+                if *count == 0 {
+                    problems.push(format!("等级 {level} 的奖励物品 {id} 数量为 0"));
+                }
+            }
+        }
+        problems
+    }
+}
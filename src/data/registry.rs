@@ -0,0 +1,191 @@
+//! 物品注册表：加载完成后由合并的物品表构建一次，运行时只通过它查物品
+//!
+//! - `ItemId` 是注册表下标，`ItemStack` 等运行时结构只存它
+//! - 按 id / uuid / 名称的哈希索引（id 与名称忽略大小写）
+//! - CLI 用的前缀 / 模糊搜索
+
+use bevy::prelude::*;
+use std::collections::HashMap;
+use std::fmt;
+use uuid::Uuid;
+
+use super::schema::{ItemEntry, ItemList};
+
+/// 紧凑的物品句柄（注册表下标）；热重载时同一 id 的物品保持不变
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct ItemId(pub u32);
+
+impl fmt::Display for ItemId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{}", self.0)
+    }
+}
+
+/// 物品的稳定 UUID：固定 namespace + id 字节生成版本 5 UUID，同一 id 永远得到同一值
+pub fn uuid_from_id(id: &str) -> Uuid {
+    Uuid::new_v5(&Uuid::NAMESPACE_OID, id.as_bytes())
+}
+
+#[derive(Resource, Default)]
+pub struct ItemRegistry {
+    entries: Vec<ItemEntry>,
+    uuids: Vec<Uuid>,
+    /// 已从数据中删除、只为已持有的物品保留的条目
+    retired: Vec<bool>,
+    /// 已删除条目的 id 索引，之后重新加入同一 id 时复用原 `ItemId`
+    retired_ids: HashMap<String, ItemId>,
+    by_id: HashMap<String, ItemId>,
+    by_uuid: HashMap<Uuid, ItemId>,
+    by_name: HashMap<String, ItemId>,
+}
+
+impl ItemRegistry {
+    pub fn from_list(list: &ItemList) -> Self {
+        let mut registry = Self::default();
+        registry.reload(list);
+        registry
+    }
+
+    /// 用新物品表更新：已有 id 保留原 `ItemId` 并替换定义，新 id 追加；
+    /// 表中已删除的物品保留旧定义（已持有的物品不受影响），但不再能被查到，
+    /// 之后重新加入时复用原 `ItemId`。返回定义有变化的物品
+    pub fn reload(&mut self, list: &ItemList) -> Vec<ItemId> {
+        let mut changed = Vec::new();
+        let mut listed = vec![false; self.entries.len()];
+
+        for entry in &list.items {
+            let key = entry.id.to_lowercase();
+            if let Some(item) = self.retired_ids.remove(&key) {
+                self.by_id.insert(key.clone(), item);
+                self.by_uuid.insert(self.uuids[item.0 as usize], item);
+            }
+            match self.by_id.get(&key).copied() {
+                Some(item) => {
+                    let idx = item.0 as usize;
+                    listed[idx] = true;
+                    if self.entries[idx] != *entry {
+                        self.remove_name(item);
+                        self.entries[idx] = entry.clone();
+                        changed.push(item);
+                    }
+                }
+                None => {
+                    let item = ItemId(self.entries.len() as u32);
+                    let uuid = uuid_from_id(&entry.id);
+                    self.entries.push(entry.clone());
+                    self.uuids.push(uuid);
+                    self.retired.push(false);
+                    listed.push(true);
+                    self.by_id.insert(key, item);
+                    self.by_uuid.insert(uuid, item);
+                }
+            }
+        }
+
+        for (idx, listed) in listed.into_iter().enumerate() {
+            let item = ItemId(idx as u32);
+            let entry = &self.entries[idx];
+            if listed {
+                self.retired[idx] = false;
+                self.by_name.insert(entry.name.to_lowercase(), item);
+            } else if !self.retired[idx] {
+                warn!("物品 {} 已不在物品表中，保留旧定义", entry.id);
+                self.retired[idx] = true;
+                let key = entry.id.to_lowercase();
+                self.by_id.remove(&key);
+                self.retired_ids.insert(key, item);
+                self.by_uuid.remove(&self.uuids[idx]);
+                self.remove_name(item);
+            }
+        }
+
+        changed
+    }
+
+    /// 去掉名称索引，名称已被其他物品占用时保留
+    fn remove_name(&mut self, item: ItemId) {
+        let name = self.get(item).name.to_lowercase();
+        if self.by_name.get(&name) == Some(&item) {
+            self.by_name.remove(&name);
+        }
+    }
+
+    /// 取定义；`ItemId` 只能来自本注册表，越界属于编程错误
+    pub fn get(&self, item: ItemId) -> &ItemEntry {
+        &self.entries[item.0 as usize]
+    }
+
+    pub fn uuid(&self, item: ItemId) -> Uuid {
+        self.uuids[item.0 as usize]
+    }
+
+    /// 当前可用的物品数量（不含已删除的）
+    pub fn len(&self) -> usize {
+        self.retired.iter().filter(|retired| !**retired).count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// 按数据顺序遍历当前可用的物品
+    pub fn iter(&self) -> impl Iterator<Item = (ItemId, &ItemEntry)> {
+        self.entries
+            .iter()
+            .enumerate()
+            .filter(|(idx, _)| !self.retired[*idx])
+            .map(|(idx, entry)| (ItemId(idx as u32), entry))
+    }
+
+    pub fn by_id(&self, id: &str) -> Option<ItemId> {
+        self.by_id.get(&id.to_lowercase()).copied()
+    }
+
+    pub fn by_uuid(&self, uuid: &Uuid) -> Option<ItemId> {
+        self.by_uuid.get(uuid).copied()
+    }
+
+    pub fn by_name(&self, name: &str) -> Option<ItemId> {
+        self.by_name.get(&name.to_lowercase()).copied()
+    }
+
+    /// 精确查找：依次按 id、uuid、名称
+    pub fn lookup(&self, token: &str) -> Option<ItemId> {
+        self.by_id(token)
+            .or_else(|| Uuid::parse_str(token).ok().and_then(|uuid| self.by_uuid(&uuid)))
+            .or_else(|| self.by_name(token))
+    }
+
+    /// CLI 搜索：id / 名称前缀优先，其次包含，最后是 id 的子序列（`swir` → `sword_iron`）
+    pub fn search(&self, query: &str) -> Vec<ItemId> {
+        let query = query.to_lowercase();
+        if query.is_empty() {
+            return Vec::new();
+        }
+
+        let mut hits: Vec<(u8, ItemId)> = self
+            .iter()
+            .filter_map(|(item, entry)| {
+                let id = entry.id.to_lowercase();
+                let name = entry.name.to_lowercase();
+                let rank = if id.starts_with(&query) || name.starts_with(&query) {
+                    0
+                } else if id.contains(&query) || name.contains(&query) {
+                    1
+                } else if is_subsequence(&query, &id) {
+                    2
+                } else {
+                    return None;
+                };
+                Some((rank, item))
+            })
+            .collect();
+        hits.sort_by_key(|&(rank, item)| (rank, item.0));
+        hits.into_iter().map(|(_, item)| item).collect()
+    }
+}
+
+fn is_subsequence(needle: &str, haystack: &str) -> bool {
+    let mut rest = haystack.chars();
+    needle.chars().all(|c| rest.any(|h| h == c))
+}
@@ -1,7 +1,13 @@
 use bevy::asset::Asset;
 use bevy::reflect::TypePath;
-use serde::Deserialize;
-#[derive(Debug, Deserialize, Clone, Default)]
+use serde::{Deserialize, Deserializer, Serialize};
+use std::fmt;
+
+use super::validate::SourceSpan;
+use crate::character::modifiers::{ModifierOp, Stat};
+
+#[derive(Debug, Deserialize, Clone, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
 pub struct ItemEntry {
     pub id: String,
     pub name: String,
@@ -13,15 +19,198 @@ pub struct ItemEntry {
     pub max_hp: i32,
     #[serde(default)]
     pub rng: i32,
+    pub kind: ItemKind, // Weapon(hands: 1), Potion(heal: 25), Key(door_id: "..."), ...
+}
+
+/// 物品类型及其专属数据；拼错的类型或缺字段在加载时即报错
+#[derive(Debug, Deserialize, Clone, PartialEq, Default)]
+#[serde(deny_unknown_fields)]
+pub enum ItemKind {
+    Weapon {
+        #[serde(default = "one_hand")]
+        hands: u8,
+    },
+    Armor {
+        slot: ArmorSlot,
+    },
+    Potion {
+        heal: i32,
+    },
+    Scroll {
+        effect: EffectSpec,
+    },
+    Key {
+        door_id: String,
+    },
+    /// 杂物：不能使用也不能装备
+    #[default]
+    Misc,
+}
+
+fn one_hand() -> u8 {
+    1
+}
+
+/// 状态效果声明：卷轴使用后授予，存档中随角色一起保存
+///
+/// ```text
+/// (id: "regen", name: "回春", duration: Turns(3), stacking: Intensity(max: 3), tick_heal: 2)
+/// ```
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct EffectSpec {
+    pub id: String,
+    pub name: String,
+    pub duration: EffectDuration,
     #[serde(default)]
-    pub heal: i32,
-    #[serde(default)]
-    pub item_type: String, // "weapon", "armor", "potion", "scroll", "key", etc.
+    pub stacking: Stacking,
+    /// 每层的属性修正（倍率 / 覆盖 / 上下限不随层数叠加）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub modifiers: Vec<EffectModifier>,
+    /// 每次发作的伤害（每层）
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub tick_damage: i32,
+    /// 每次发作的治疗量（每层）
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub tick_heal: i32,
+    /// 每隔几个回合 / tick 发作一次
+    #[serde(default = "every_unit", skip_serializing_if = "is_one")]
+    pub interval: u32,
+}
+
+fn is_zero(value: &i32) -> bool {
+    *value == 0
+}
+
+fn every_unit() -> u32 {
+    1
+}
+
+fn is_one(value: &u32) -> bool {
+    *value == 1
+}
+
+/// 持续时间：回合 = 一条游戏命令；tick = `InGame` 中的一帧
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum EffectDuration {
+    Turns(u32),
+    Ticks(u32),
+}
+
+impl EffectDuration {
+    pub fn amount(self) -> u32 {
+        match self {
+            EffectDuration::Turns(n) | EffectDuration::Ticks(n) => n,
+        }
+    }
+
+    /// 单位名称
+    pub fn unit(self) -> &'static str {
+        match self {
+            EffectDuration::Turns(_) => "回合",
+            EffectDuration::Ticks(_) => "tick",
+        }
+    }
+}
+
+/// 同一效果再次施加时的规则
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Stacking {
+    /// 重置持续时间
+    #[default]
+    Refresh,
+    /// 层数 +1（不超过 `max`）并重置持续时间
+    Intensity { max: u32 },
+    /// 已存在时不生效
+    Unique,
+}
+
+/// 效果附带的属性修正
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct EffectModifier {
+    pub stat: Stat,
+    pub op: ModifierOp,
+}
+
+/// 防具可占用的装备槽（武器固定占 weapon 槽）
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum ArmorSlot {
+    Head,
+    Body,
+    Accessory,
+}
+
+impl ArmorSlot {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ArmorSlot::Head => "head",
+            ArmorSlot::Body => "body",
+            ArmorSlot::Accessory => "accessory",
+        }
+    }
+}
+
+impl ItemKind {
+    /// 可装备到的槽位；不可装备的类型为 None
+    pub fn equip_slot(&self) -> Option<&'static str> {
+        match self {
+            ItemKind::Weapon { .. } => Some("weapon"),
+            ItemKind::Armor { slot } => Some(slot.as_str()),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ItemKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ItemKind::Weapon { hands } => write!(f, "weapon (hands {hands})"),
+            ItemKind::Armor { slot } => write!(f, "armor (slot {})", slot.as_str()),
+            ItemKind::Potion { heal } => write!(f, "potion (heal {heal})"),
+            ItemKind::Scroll { effect } => write!(f, "scroll (effect {})", effect.id),
+            ItemKind::Key { door_id } => write!(f, "key (door {door_id})"),
+            ItemKind::Misc => write!(f, "misc"),
+        }
+    }
 }
 
 #[derive(Asset, TypePath, Deserialize, Debug)]
 pub struct ItemList {
     pub items: Vec<ItemEntry>,
+    /// 来源文件（加载时填入，用于报错）
+    #[serde(skip)]
+    pub source: String,
+    /// 与 `items` 一一对应的源文件位置（加载时填入）
+    #[serde(skip)]
+    pub spans: Vec<SourceSpan>,
+    /// 源文件内容哈希（加载时填入，回放时核对数据是否与录制时相同）
+    #[serde(skip)]
+    pub hash: String,
+}
+
+/// 数据清单（`data/manifest.ron`）：按顺序列出各类数据文件，
+/// 后列出的文件可以新增条目，或按 id 覆盖前面文件中的条目
+#[derive(Asset, TypePath, Deserialize, Debug, Default)]
+#[serde(deny_unknown_fields)]
+pub struct DataManifest {
+    #[serde(default)]
+    pub items: Vec<String>,
+    /// 成长曲线文件；未指定时使用内置曲线
+    #[serde(default, deserialize_with = "implicit_some")]
+    pub progression: Option<String>,
+    /// 职业文件；未指定时没有职业选择
+    #[serde(default, deserialize_with = "implicit_some")]
+    pub classes: Option<String>,
+}
+
+/// 可选字段直接写值，不必写 `Some(...)`（RON 默认要求）
+pub(super) fn implicit_some<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    T::deserialize(deserializer).map(Some)
 }
 
 // 在顶层模块中定义常量句柄
@@ -0,0 +1,152 @@
+//! 物品表校验：反序列化只保证结构正确，这里检查跨条目 / 取值范围的问题
+//!
+//! 每个问题都带物品 id 与源文件位置，全部收集后一次性报告：
+//!
+//! ```text
+//! data/items.ron: 2 个问题
+//!   7:5  [potion_big] 重复的物品 id（首次出现于 4:5）
+//!   9:5  [bad_potion] heal 不能为负数: -5
+//! ```
+//!
+//! 合并后的物品表也会再校验一次，用来发现不同文件之间的重名物品
+use std::collections::HashMap;
+use std::fmt;
+
+use super::schema::{EffectSpec, ItemKind, ItemList, Stacking};
+
+/// 源文件位置（从 1 开始）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SourceSpan {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl fmt::Display for SourceSpan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+/// 单条问题
+#[derive(Debug, Clone, PartialEq)]
+pub struct ItemIssue {
+    pub item_id: String,
+    pub span: Option<SourceSpan>,
+    pub message: String,
+}
+
+/// 一个物品表的全部问题
+#[derive(Debug, Clone, Default)]
+pub struct ValidationReport {
+    pub source: String,
+    pub issues: Vec<ItemIssue>,
+}
+
+impl ValidationReport {
+    pub fn is_ok(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} 个问题", self.source, self.issues.len())?;
+        for issue in &self.issues {
+            let span = issue.span.map_or_else(|| "?".to_string(), |s| s.to_string());
+            write!(f, "\n  {span:<5} [{}] {}", issue.item_id, issue.message)?;
+        }
+        Ok(())
+    }
+}
+
+/// 校验整张物品表
+pub fn validate(list: &ItemList) -> ValidationReport {
+    let mut issues = Vec::new();
+    let mut seen: HashMap<String, Option<SourceSpan>> = HashMap::new();
+    let mut names: HashMap<String, &str> = HashMap::new();
+
+    for (idx, item) in list.items.iter().enumerate() {
+        let span = list.spans.get(idx).copied();
+        let mut report = |message: String| {
+            issues.push(ItemIssue {
+                item_id: item.id.clone(),
+                span,
+                message,
+            })
+        };
+
+        if item.id.trim().is_empty() {
+            report("物品 id 为空".into());
+        } else if item.id.chars().any(char::is_whitespace) {
+            report("物品 id 不能包含空白".into());
+        }
+        // give / items 按 id 忽略大小写查找，大小写不同也算重复
+        match seen.get(&item.id.to_lowercase()) {
+            Some(first) => {
+                let first = first.map_or_else(|| "?".to_string(), |s| s.to_string());
+                report(format!("重复的物品 id（首次出现于 {first}）"));
+            }
+            None => {
+                seen.insert(item.id.to_lowercase(), span);
+            }
+        }
+        if item.name.trim().is_empty() {
+            report("物品名称为空".into());
+        } else {
+            // give 也可以按名称（忽略大小写）查找，重名时无法确定是哪一件
+            match names.get(&item.name.to_lowercase()) {
+                Some(first) => report(format!("物品名称 \"{}\" 与 {first} 重复", item.name)),
+                None => {
+                    names.insert(item.name.to_lowercase(), &item.id);
+                }
+            }
+        }
+
+        match &item.kind {
+            ItemKind::Potion { heal } if *heal < 0 => {
+                report(format!("heal 不能为负数: {heal}"));
+            }
+            ItemKind::Weapon { hands } if !(1..=2).contains(hands) => {
+                report(format!("hands 只能是 1 或 2: {hands}"));
+            }
+            ItemKind::Scroll { effect } => {
+                for problem in effect_problems(effect) {
+                    report(problem);
+                }
+            }
+            ItemKind::Key { door_id } if door_id.trim().is_empty() => {
+                report("钥匙 door_id 为空".into());
+            }
+            _ => {}
+        }
+    }
+
+    ValidationReport {
+        source: list.source.clone(),
+        issues,
+    }
+}
+
+/// 卷轴声明的效果：id / 名称非空、持续时间与发作间隔为正、至少有一种作用
+fn effect_problems(effect: &EffectSpec) -> Vec<String> {
+    let mut problems = Vec::new();
+    if effect.id.trim().is_empty() || effect.id.chars().any(char::is_whitespace) {
+        problems.push(format!("效果 id \"{}\" 不能为空或包含空白", effect.id));
+    }
+    if effect.name.trim().is_empty() {
+        problems.push("效果名称为空".into());
+    }
+    if effect.duration.amount() == 0 {
+        problems.push("效果持续时间必须 > 0".into());
+    }
+    if effect.interval == 0 {
+        problems.push("效果 interval 必须 > 0".into());
+    }
+    if effect.stacking == (Stacking::Intensity { max: 0 }) {
+        problems.push("Intensity 的 max 必须 > 0".into());
+    }
+    if effect.modifiers.is_empty() && effect.tick_damage == 0 && effect.tick_heal == 0 {
+        problems.push(format!("效果 {} 没有任何作用", effect.id));
+    }
+    problems
+}
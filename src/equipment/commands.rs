@@ -0,0 +1,40 @@
+use super::{components::Equipment, events::*};
+use crate::interface::command::{
+    ArgKind, ArgSpec, CliCommand, CommandArgs, CommandSpec, CommandType,
+};
+
+fn slot_arg() -> ArgSpec {
+    ArgSpec::required("slot", ArgKind::Choice(Equipment::all_slots()), "装备槽")
+}
+
+impl CliCommand for EquipEvent {
+    fn spec() -> CommandSpec {
+        CommandSpec::new("equip", CommandType::Game, "装备物品")
+            .target()
+            .arg(slot_arg())
+            .arg(ArgSpec::required("index", ArgKind::UInt, "背包索引"))
+    }
+
+    fn from_args(args: &CommandArgs) -> Self {
+        Self {
+            target: args.target(),
+            slot: args.get("slot"),
+            index: args.get("index"),
+        }
+    }
+}
+
+impl CliCommand for UnequipEvent {
+    fn spec() -> CommandSpec {
+        CommandSpec::new("unequip", CommandType::Game, "卸下装备")
+            .target()
+            .arg(slot_arg())
+    }
+
+    fn from_args(args: &CommandArgs) -> Self {
+        Self {
+            target: args.target(),
+            slot: args.get("slot"),
+        }
+    }
+}
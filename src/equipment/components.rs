@@ -1,8 +1,8 @@
 use crate::inventory::components::ItemStack;
 use bevy::prelude::*;
 
-/// 装备系统 - 包含四个装备槽
-#[derive(Resource, Default)]
+/// 装备系统 - 包含四个装备槽（挂在任意角色实体上）
+#[derive(Component, Default)]
 pub struct Equipment {
     pub head: Option<ItemStack>,      // 头部装备
     pub body: Option<ItemStack>,      // 身体装备
@@ -33,6 +33,13 @@ impl Equipment {
         }
     }
 
+    /// 所有已装备的物品
+    pub fn equipped(&self) -> impl Iterator<Item = &ItemStack> {
+        [&self.head, &self.body, &self.weapon, &self.accessory]
+            .into_iter()
+            .flatten()
+    }
+
     /// 检查槽位名称是否有效
     pub fn is_valid_slot(slot: &str) -> bool {
         matches!(slot, "head" | "body" | "weapon" | "accessory")
@@ -1,12 +1,15 @@
+use crate::character::target::Target;
 use bevy::prelude::*;
 
 #[derive(Event)]
 pub struct EquipEvent {
-    pub slot: String, // head, body, weapon, accessory
-    pub index: usize, // 背包索引
+    pub target: Target, // 目标实体
+    pub slot: String,   // head, body, weapon, accessory
+    pub index: usize,   // 背包索引
 }
 
 #[derive(Event)]
 pub struct UnequipEvent {
-    pub slot: String, // head, body, weapon, accessory
+    pub target: Target, // 目标实体
+    pub slot: String,   // head, body, weapon, accessory
 }
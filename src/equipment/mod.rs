@@ -1,22 +1,24 @@
+pub mod commands;
 pub mod components;
 pub mod events;
 mod systems;
 
 use crate::core::states::AppState;
+use crate::interface::command::CliAppExt;
 use bevy::prelude::*;
-use components::*;
 use events::*;
 use systems::*;
 
 pub struct EquipmentPlugin;
 impl Plugin for EquipmentPlugin {
     fn build(&self, app: &mut App) {
-        app.insert_resource(Equipment::default())
-            .add_event::<EquipEvent>()
-            .add_event::<UnequipEvent>()
+        app
+            // 事件 + CLI 命令：equip / unequip
+            .register_cli_command::<EquipEvent>()
+            .register_cli_command::<UnequipEvent>()
             .add_systems(
                 Update,
-                (equip_item, unequip_item).run_if(in_state(AppState::InGame)),
+                (equip_item, unequip_item, refresh_equipment).run_if(in_state(AppState::InGame)),
             );
     }
 }
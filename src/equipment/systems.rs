@@ -1,17 +1,24 @@
 use super::{components::*, events::*};
-use crate::character::events::EquipmentChanged;
+use crate::character::components::CharacterClass;
+use crate::character::events::{EquipmentChanged, RecalculateStats};
+use crate::character::target::{Target, Targets};
 use crate::core::events::LogEvent;
+use crate::data::classes::{Classes, EquipType};
+use crate::data::{events::ItemsReloaded, registry::ItemRegistry};
 use crate::inventory::{components::Backpack, events::ListInventoryEvent};
 use bevy::prelude::*;
 
+/// 装备物品：检查槽位与职业可用的装备类型
+#[allow(clippy::too_many_arguments)]
 pub fn equip_item(
     mut ev_equip: EventReader<EquipEvent>,
-    mut equip: ResMut<Equipment>,
-    mut backpack: ResMut<Backpack>,
+    mut actors: Query<(&mut Equipment, &mut Backpack, Option<&CharacterClass>)>,
     mut list_event: EventWriter<ListInventoryEvent>,
     mut equipment_changed: EventWriter<EquipmentChanged>,
     mut log_event: EventWriter<LogEvent>,
-    player_query: Query<Entity, With<crate::character::components::Player>>,
+    targets: Targets,
+    registry: Res<ItemRegistry>,
+    classes: Res<Classes>,
 ) {
     for ev in ev_equip.read() {
         // 检查槽位是否有效
@@ -20,71 +27,107 @@ pub fn equip_item(
             continue;
         }
 
-        // 检查背包索引是否有效
-        if ev.index >= backpack.slots.len() {
-            log_event.write(LogEvent("背包索引超出范围".to_string()));
-            continue;
+        let resolved = targets.resolve(&ev.target);
+        if resolved.is_empty() {
+            log_event.write(LogEvent(format!("未找到目标 {}", ev.target)));
         }
 
-        // 先检查背包索引和物品
-        let (item_proto, item_name) = {
-            if let Some(stack) = backpack.slots.get(ev.index) {
-                if stack.count == 0 {
-                    log_event.write(LogEvent("该背包格为空".to_string()));
+        for entity in resolved {
+            let Ok((mut equip, mut backpack, class)) = actors.get_mut(entity) else {
+                log_event.write(LogEvent("目标无法装备物品".to_string()));
+                continue;
+            };
+
+            // 检查背包索引是否有效
+            if ev.index >= backpack.slots.len() {
+                log_event.write(LogEvent("背包索引超出范围".to_string()));
+                continue;
+            }
+
+            // 先检查背包索引和物品
+            let (item, item_proto) = {
+                if let Some(stack) = backpack.slots.get(ev.index) {
+                    if stack.count == 0 {
+                        log_event.write(LogEvent("该背包格为空".to_string()));
+                        continue;
+                    }
+                    (stack.item, registry.get(stack.item))
+                } else {
                     continue;
                 }
-                (stack.proto.clone(), stack.proto.name.clone())
-            } else {
+            };
+            let item_name = &item_proto.name;
+
+            // 按物品类型检查能否装备到该槽位
+            match item_proto.kind.equip_slot() {
+                Some(slot) if slot == ev.slot => {}
+                Some(slot) => {
+                    log_event.write(LogEvent(format!("{} 只能装备在 {} 槽", item_name, slot)));
+                    continue;
+                }
+                None => {
+                    log_event.write(LogEvent(format!("{} 无法装备", item_name)));
+                    continue;
+                }
+            }
+
+            // 职业限制（没有职业或职业已不存在时不限制）
+            if let Some(class) = class.and_then(|class| classes.get(&class.0))
+                && let Some(kind) = EquipType::of(&item_proto.kind)
+                && !class.allows(kind)
+            {
+                log_event.write(LogEvent(format!(
+                    "{}无法装备 {}：{}不能使用{}",
+                    targets.label(entity),
+                    item_name,
+                    class.name,
+                    kind.label()
+                )));
                 continue;
             }
-        };
-
-        // 检查是否有旧装备需要放回背包
-        let old_item = if let Some(slot_ref) = equip.get_slot_mut(&ev.slot) {
-            slot_ref.take()
-        } else {
-            None
-        };
-
-        // 如果有旧装备，尝试放回背包
-        if let Some(old_item) = old_item {
-            if let Some(empty_slot) = backpack.slots.iter_mut().find(|s| s.count == 0) {
-                *empty_slot = old_item;
+
+            // 检查是否有旧装备需要放回背包
+            let old_item = if let Some(slot_ref) = equip.get_slot_mut(&ev.slot) {
+                slot_ref.take()
             } else {
-                log_event.write(LogEvent("背包已满，无法卸下原装备".to_string()));
-                // 恢复原装备
-                if let Some(slot_ref) = equip.get_slot_mut(&ev.slot) {
-                    *slot_ref = Some(old_item);
+                None
+            };
+
+            // 如果有旧装备，尝试放回背包
+            if let Some(old_item) = old_item {
+                if let Some(empty_slot) = backpack.slots.iter_mut().find(|s| s.count == 0) {
+                    *empty_slot = old_item;
+                } else {
+                    log_event.write(LogEvent("背包已满，无法卸下原装备".to_string()));
+                    // 恢复原装备
+                    if let Some(slot_ref) = equip.get_slot_mut(&ev.slot) {
+                        *slot_ref = Some(old_item);
+                    }
+                    continue;
                 }
-                continue;
             }
-        }
 
-        // 从背包中取出物品
-        if let Some(stack) = backpack.slots.get_mut(ev.index) {
-            stack.count -= 1;
+            // 从背包中取出物品
+            if let Some(stack) = backpack.slots.get_mut(ev.index) {
+                stack.count -= 1;
 
-            // 装备新物品
-            let taken = crate::inventory::components::ItemStack {
-                proto: item_proto,
-                count: 1,
-            };
+                // 装备新物品
+                let taken = crate::inventory::components::ItemStack::new(item, 1);
 
-            if let Some(slot_ref) = equip.get_slot_mut(&ev.slot) {
-                *slot_ref = Some(taken);
-            }
+                if let Some(slot_ref) = equip.get_slot_mut(&ev.slot) {
+                    *slot_ref = Some(taken);
+                }
 
-            log_event.write(LogEvent(format!("已装备 {}: {}", ev.slot, item_name)));
+                log_event.write(LogEvent(format!("已装备 {}: {}", ev.slot, item_name)));
 
-            // 触发装备变更事件
-            if let Ok(player_entity) = player_query.single() {
-                equipment_changed.write(EquipmentChanged {
-                    entity: player_entity,
+                // 触发装备变更事件
+                equipment_changed.write(EquipmentChanged { entity });
+
+                // 刷新背包显示
+                list_event.write(ListInventoryEvent {
+                    target: Target::Entity(entity),
                 });
             }
-
-            // 刷新背包显示
-            list_event.write(ListInventoryEvent);
         }
     }
 }
@@ -92,12 +135,12 @@ pub fn equip_item(
 /// 卸下装备
 pub fn unequip_item(
     mut ev_unequip: EventReader<UnequipEvent>,
-    mut equip: ResMut<Equipment>,
-    mut backpack: ResMut<Backpack>,
+    mut actors: Query<(&mut Equipment, &mut Backpack)>,
     mut list_event: EventWriter<ListInventoryEvent>,
     mut equipment_changed: EventWriter<EquipmentChanged>,
     mut log_event: EventWriter<LogEvent>,
-    player_query: Query<Entity, With<crate::character::components::Player>>,
+    targets: Targets,
+    registry: Res<ItemRegistry>,
 ) {
     for ev in ev_unequip.read() {
         // 检查槽位是否有效
@@ -106,29 +149,55 @@ pub fn unequip_item(
             continue;
         }
 
-        if let Some(slot_ref) = equip.get_slot_mut(&ev.slot) {
-            if let Some(item) = slot_ref.take() {
-                // 尝试放回背包
-                if let Some(empty_slot) = backpack.slots.iter_mut().find(|s| s.count == 0) {
-                    *empty_slot = item.clone();
-                    log_event.write(LogEvent(format!("已卸下 {}: {}", ev.slot, item.proto.name)));
+        let resolved = targets.resolve(&ev.target);
+        if resolved.is_empty() {
+            log_event.write(LogEvent(format!("未找到目标 {}", ev.target)));
+        }
 
-                    // 触发装备变更事件
-                    if let Ok(player_entity) = player_query.single() {
-                        equipment_changed.write(EquipmentChanged {
-                            entity: player_entity,
+        for entity in resolved {
+            let Ok((mut equip, mut backpack)) = actors.get_mut(entity) else {
+                log_event.write(LogEvent("目标无法装备物品".to_string()));
+                continue;
+            };
+
+            if let Some(slot_ref) = equip.get_slot_mut(&ev.slot) {
+                if let Some(item) = slot_ref.take() {
+                    // 尝试放回背包
+                    if let Some(empty_slot) = backpack.slots.iter_mut().find(|s| s.count == 0) {
+                        *empty_slot = item.clone();
+                        let name = &registry.get(item.item).name;
+                        log_event.write(LogEvent(format!("已卸下 {}: {}", ev.slot, name)));
+
+                        // 触发装备变更事件
+                        equipment_changed.write(EquipmentChanged { entity });
+
+                        // 刷新背包显示
+                        list_event.write(ListInventoryEvent {
+                            target: Target::Entity(entity),
                         });
+                    } else {
+                        // 背包满了，恢复装备
+                        *slot_ref = Some(item);
+                        log_event.write(LogEvent("背包已满，无法卸下装备".to_string()));
                     }
-
-                    // 刷新背包显示
-                    list_event.write(ListInventoryEvent);
                 } else {
-                    // 背包满了，恢复装备
-                    *slot_ref = Some(item);
-                    log_event.write(LogEvent("背包已满，无法卸下装备".to_string()));
+                    log_event.write(LogEvent(format!("{} 槽位为空", ev.slot)));
                 }
-            } else {
-                log_event.write(LogEvent(format!("{} 槽位为空", ev.slot)));
+            }
+        }
+    }
+}
+
+/// 物品数据热重载后，为装备了定义有变化物品的实体重算属性
+pub fn refresh_equipment(
+    mut ev_reloaded: EventReader<ItemsReloaded>,
+    mut ev_recalculate: EventWriter<RecalculateStats>,
+    actors: Query<(Entity, &Equipment)>,
+) {
+    for ev in ev_reloaded.read() {
+        for (entity, equipment) in &actors {
+            if equipment.equipped().any(|stack| ev.changed.contains(&stack.item)) {
+                ev_recalculate.write(RecalculateStats { entity });
             }
         }
     }
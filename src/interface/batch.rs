@@ -0,0 +1,221 @@
+//! 批处理模式：按行执行脚本文件 / 管道输入，输出纯文本 transcript 后退出
+//!
+//! 每条命令发出后，等到所有游戏事件队列都清空（即事件链 `CliLine → EquipEvent →
+//! EquipmentChanged → RecalculateStats …` 已全部处理完）再喂下一行。
+//!
+//! 退出码：0 = 全部命令被接受；1 = 存在无法解析的命令（未知命令 / 参数错误）；
+//! 2 = 脚本 / transcript 无法读写
+
+use bevy::app::AppExit;
+use bevy::prelude::*;
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::num::NonZero;
+
+use super::command::CommandRegistry;
+use super::debug_cli::{
+    CliLine, CliMessage, drain_outputs, log_cli_input, queue_output, set_plain_output,
+};
+use crate::character::events::{Death, EquipmentChanged, LevelUp, RecalculateStats};
+use crate::core::args::{LaunchArgs, ScriptSource};
+use crate::core::{
+    events::LogEvent,
+    states::{AppState, accepts_input},
+};
+use crate::data::{DataLoading, events::ItemsReloaded};
+use crate::status::events::ApplyEffect;
+
+/// 批处理脚本状态
+#[derive(Resource)]
+pub struct CliScript {
+    lines: VecDeque<String>,
+    sink: Box<dyn Write + Send + Sync>,
+    failures: u32,
+    pub(super) io_error: bool,
+}
+
+/// 本帧是否没有任何待处理的游戏事件（在 Last 中计算，供下一帧喂入命令的系统使用）
+#[derive(Resource, Default)]
+pub(super) struct CliIdle(pub(super) bool);
+
+pub(super) fn build(app: &mut App, source: ScriptSource) {
+    let mut io_error = false;
+    let lines = read_script(&source).unwrap_or_else(|err| {
+        eprintln!("无法读取脚本 {source:?}: {err}");
+        io_error = true;
+        VecDeque::new()
+    });
+    install(app, lines, io_error);
+
+    // 与 stdin 读取一样，喂入的行必须先被回显再执行
+    app.add_systems(
+        Update,
+        feed_script
+            .run_if(accepts_input)
+            .before(log_cli_input),
+    );
+}
+
+/// 无人值守运行的公共部分（批处理与回放共用）：纯文本 transcript、空闲检测
+pub(super) fn install(app: &mut App, lines: VecDeque<String>, mut io_error: bool) {
+    set_plain_output(true);
+
+    let transcript = app
+        .world()
+        .get_resource::<LaunchArgs>()
+        .and_then(|args| args.transcript.clone());
+
+    let sink: Box<dyn Write + Send + Sync> = match transcript.map(File::create) {
+        None => Box::new(io::stdout()),
+        Some(Ok(file)) => Box::new(file),
+        Some(Err(err)) => {
+            eprintln!("无法创建 transcript: {err}");
+            io_error = true;
+            Box::new(io::stdout())
+        }
+    };
+
+    app.insert_resource(CliScript {
+        lines,
+        sink,
+        failures: 0,
+        io_error,
+    })
+    .init_resource::<CliIdle>()
+    .add_systems(
+        Last,
+        (
+            begin_idle_check,
+            (
+                // 各插件注册的命令事件
+                track_command_events,
+                // 命令事件触发的后续事件
+                track_events::<CliLine>,
+                track_events::<LogEvent>,
+                track_events::<EquipmentChanged>,
+                track_events::<LevelUp>,
+                track_events::<Death>,
+                track_events::<RecalculateStats>,
+                track_events::<ApplyEffect>,
+                track_events::<ItemsReloaded>,
+                track_data_reload,
+                track_state_transition,
+            ),
+            write_transcript,
+        )
+            .chain(),
+    );
+}
+
+/// 读取脚本：跳过空行与 `#` 注释
+fn read_script(source: &ScriptSource) -> io::Result<VecDeque<String>> {
+    let reader: Box<dyn Read> = match source {
+        ScriptSource::File(path) => Box::new(File::open(path)?),
+        ScriptSource::Stdin => Box::new(io::stdin()),
+    };
+
+    let mut lines = VecDeque::new();
+    for line in BufReader::new(reader).lines() {
+        let line = line?;
+        let line = line.trim();
+        if !line.is_empty() && !line.starts_with('#') {
+            lines.push_back(line.to_string());
+        }
+    }
+    Ok(lines)
+}
+
+/// 空闲时喂入下一行；脚本耗尽后带退出码结束
+fn feed_script(
+    mut script: ResMut<CliScript>,
+    idle: Res<CliIdle>,
+    registry: Res<CommandRegistry>,
+    state: Res<State<AppState>>,
+    mut writer: EventWriter<CliLine>,
+    mut app_exit: EventWriter<AppExit>,
+) {
+    if !idle.0 {
+        return;
+    }
+
+    let Some(line) = script.lines.pop_front() else {
+        let code = if script.io_error {
+            2
+        } else if script.failures > 0 {
+            1
+        } else {
+            0
+        };
+        app_exit.write(match NonZero::new(code) {
+            Some(code) => AppExit::Error(code),
+            None => AppExit::Success,
+        });
+        return;
+    };
+
+    match registry.parse_in(&line, *state.get() == AppState::InGame) {
+        // exit 只结束脚本，退出码仍由脚本结果决定
+        Ok(command) if command.name == "exit" => {
+            queue_output(CliMessage::UserInput(line, command.kind));
+            script.lines.clear();
+        }
+        parsed => {
+            if parsed.is_err() {
+                script.failures += 1;
+            }
+            writer.write(CliLine(line));
+        }
+    }
+}
+
+fn begin_idle_check(mut idle: ResMut<CliIdle>) {
+    idle.0 = true;
+}
+
+fn track_command_events(world: &mut World) {
+    if world
+        .resource::<CommandRegistry>()
+        .has_pending_events(world)
+    {
+        world.resource_mut::<CliIdle>().0 = false;
+    }
+}
+
+fn track_events<E: Event>(events: Res<Events<E>>, mut idle: ResMut<CliIdle>) {
+    if !events.is_empty() {
+        idle.0 = false;
+    }
+}
+
+/// `reload` 在后台线程读文件，等结果回来再继续
+fn track_data_reload(loading: Res<DataLoading>, mut idle: ResMut<CliIdle>) {
+    if loading.is_reloading() {
+        idle.0 = false;
+    }
+}
+
+/// `new_game` 之后要等切换到 `InGame`、玩家生成后再继续
+fn track_state_transition(next: Res<NextState<AppState>>, mut idle: ResMut<CliIdle>) {
+    if matches!(*next, NextState::Pending(_)) {
+        idle.0 = false;
+    }
+}
+
+/// 将本帧输出写入 transcript
+fn write_transcript(mut script: ResMut<CliScript>) {
+    let outputs = drain_outputs();
+    if outputs.is_empty() {
+        return;
+    }
+
+    let sink = &mut script.sink;
+    let result = outputs
+        .iter()
+        .try_for_each(|line| writeln!(sink, "{line}"))
+        .and_then(|_| sink.flush());
+    if let Err(err) = result {
+        eprintln!("写入 transcript 失败: {err}");
+        script.io_error = true;
+    }
+}
@@ -0,0 +1,562 @@
+//! 声明式命令表：每条命令声明参数类型 / 可选性 / 帮助文本，解析失败给出精确错误
+//!
+//! ```text
+//! give <id> [count=1]        → CommandSpec::new("give", ..).arg(..).arg(..)
+//! equip weapon x             → expected integer for <index>, got "x"
+//! help equip                 → 由 CommandSpec 自动生成
+//! take_damage @goblin1 5     → 声明了 .target() 的命令可用首个 `@` 参数指定目标
+//! ```
+//!
+//! 各功能插件通过 [`CliAppExt`] 注册自己的命令，CLI 只负责按动词路由：
+//!
+//! ```text
+//! impl CliCommand for GiveItemEvent { .. }
+//! app.register_cli_command::<GiveItemEvent>();
+//! ```
+
+use crate::character::target::Target;
+use bevy::prelude::*;
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::str::FromStr;
+use std::sync::Arc;
+use thiserror::Error;
+
+/// 命令类型分类
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CommandType {
+    System, // 系统命令：help, status, items, inventory等
+    Game,   // 游戏命令：gain_exp, take_damage, heal, equip, use等
+}
+
+/// 参数类型
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ArgKind {
+    /// 有符号整数（i32）
+    Int,
+    /// 非负整数（u32）
+    UInt,
+    /// 任意单词
+    Word,
+    /// 固定取值之一
+    Choice(&'static [&'static str]),
+}
+
+impl ArgKind {
+    fn accepts(&self, raw: &str) -> bool {
+        match self {
+            ArgKind::Int => raw.parse::<i32>().is_ok(),
+            ArgKind::UInt => raw.parse::<u32>().is_ok(),
+            ArgKind::Word => true,
+            ArgKind::Choice(options) => options.contains(&raw),
+        }
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            ArgKind::Int => "integer".into(),
+            ArgKind::UInt => "non-negative integer".into(),
+            ArgKind::Word => "word".into(),
+            ArgKind::Choice(options) => format!("one of {}", options.join("/")),
+        }
+    }
+}
+
+/// 位置参数声明
+#[derive(Debug, Clone)]
+pub struct ArgSpec {
+    pub name: &'static str,
+    pub kind: ArgKind,
+    pub required: bool,
+    pub default: Option<&'static str>,
+    pub help: &'static str,
+}
+
+impl ArgSpec {
+    pub fn required(name: &'static str, kind: ArgKind, help: &'static str) -> Self {
+        Self {
+            name,
+            kind,
+            required: true,
+            default: None,
+            help,
+        }
+    }
+
+    pub fn optional(name: &'static str, kind: ArgKind, help: &'static str) -> Self {
+        Self {
+            required: false,
+            ..Self::required(name, kind, help)
+        }
+    }
+
+    /// 缺省值（隐含可选）
+    pub fn default(mut self, value: &'static str) -> Self {
+        self.required = false;
+        self.default = Some(value);
+        self
+    }
+}
+
+/// `--name` 开关 或 `--name <value>` 选项
+#[derive(Debug, Clone)]
+pub struct FlagSpec {
+    pub name: &'static str,
+    /// None = 布尔开关
+    pub value: Option<ArgKind>,
+    pub required: bool,
+    pub help: &'static str,
+}
+
+impl FlagSpec {
+    pub fn switch(name: &'static str, help: &'static str) -> Self {
+        Self {
+            name,
+            value: None,
+            required: false,
+            help,
+        }
+    }
+
+    pub fn value(name: &'static str, kind: ArgKind, help: &'static str) -> Self {
+        Self {
+            name,
+            value: Some(kind),
+            required: false,
+            help,
+        }
+    }
+
+    pub fn required(mut self) -> Self {
+        self.required = true;
+        self
+    }
+}
+
+/// 一条命令的完整声明
+#[derive(Debug, Clone)]
+pub struct CommandSpec {
+    pub name: &'static str,
+    pub aliases: Vec<&'static str>,
+    pub summary: &'static str,
+    pub kind: CommandType,
+    /// 是否接受首个 `@target` 参数
+    pub target: bool,
+    /// 职业选择阶段（进入游戏之前）是否可用
+    pub pre_game: bool,
+    pub args: Vec<ArgSpec>,
+    pub flags: Vec<FlagSpec>,
+}
+
+impl CommandSpec {
+    pub fn new(name: &'static str, kind: CommandType, summary: &'static str) -> Self {
+        Self {
+            name,
+            aliases: Vec::new(),
+            summary,
+            kind,
+            target: false,
+            pre_game: false,
+            args: Vec::new(),
+            flags: Vec::new(),
+        }
+    }
+
+    pub fn alias(mut self, alias: &'static str) -> Self {
+        self.aliases.push(alias);
+        self
+    }
+
+    /// 允许用 `@target` 指定作用对象，缺省为玩家
+    pub fn target(mut self) -> Self {
+        self.target = true;
+        self
+    }
+
+    /// 进入游戏之前（职业选择阶段）也可用；其余命令要等玩家生成后才能执行
+    pub fn pre_game(mut self) -> Self {
+        self.pre_game = true;
+        self
+    }
+
+    pub fn arg(mut self, arg: ArgSpec) -> Self {
+        debug_assert!(
+            !arg.required || self.args.iter().all(|a| a.required),
+            "required argument <{}> declared after an optional one",
+            arg.name
+        );
+        self.args.push(arg);
+        self
+    }
+
+    pub fn flag(mut self, flag: FlagSpec) -> Self {
+        self.flags.push(flag);
+        self
+    }
+
+    /// 用法行：`give [@target] <id> [count=1] [--flag <v>]`
+    pub fn usage(&self) -> String {
+        let mut out = self.name.to_string();
+        if self.target {
+            out.push_str(" [@target]");
+        }
+        for arg in &self.args {
+            match (arg.required, arg.default) {
+                (true, _) => write!(out, " <{}>", arg.name),
+                (false, Some(d)) => write!(out, " [{}={d}]", arg.name),
+                (false, None) => write!(out, " [{}]", arg.name),
+            }
+            .unwrap();
+        }
+        for flag in &self.flags {
+            let body = match flag.value {
+                Some(_) => format!("--{} <{}>", flag.name, flag.name),
+                None => format!("--{}", flag.name),
+            };
+            if flag.required {
+                write!(out, " {body}").unwrap();
+            } else {
+                write!(out, " [{body}]").unwrap();
+            }
+        }
+        out
+    }
+
+    /// `help <cmd>` 的详细说明
+    pub fn help(&self) -> String {
+        let mut out = format!("{}\n  {}", self.usage(), self.summary);
+        if !self.aliases.is_empty() {
+            write!(out, "\n  别名: {}", self.aliases.join(", ")).unwrap();
+        }
+        if self.target {
+            out.push_str(
+                "\n  @target  作用目标，缺省为玩家 (@player / @3v1 / @goblin1 / @all:monster)",
+            );
+        }
+        for arg in &self.args {
+            write!(
+                out,
+                "\n  <{}>  {} ({})",
+                arg.name,
+                arg.help,
+                arg.kind.describe()
+            )
+            .unwrap();
+        }
+        for flag in &self.flags {
+            let kind = flag
+                .value
+                .map_or_else(|| "switch".to_string(), |k| k.describe());
+            write!(out, "\n  --{}  {} ({kind})", flag.name, flag.help).unwrap();
+        }
+        out
+    }
+}
+
+/// 解析错误
+#[derive(Debug, Error, PartialEq)]
+pub enum CliError {
+    #[error("不支持的命令: {0}")]
+    UnknownCommand(String),
+    #[error("missing required argument <{arg}> (usage: {usage})")]
+    MissingArgument { arg: &'static str, usage: String },
+    #[error("missing required flag --{flag} (usage: {usage})")]
+    MissingFlag { flag: &'static str, usage: String },
+    #[error("expected {expected} for <{arg}>, got \"{got}\"")]
+    InvalidArgument {
+        arg: &'static str,
+        expected: String,
+        got: String,
+    },
+    #[error("unexpected argument \"{got}\" (usage: {usage})")]
+    UnexpectedArgument { got: String, usage: String },
+    #[error("unknown flag --{flag} (usage: {usage})")]
+    UnknownFlag { flag: String, usage: String },
+    #[error("invalid target: {0}")]
+    InvalidTarget(String),
+    #[error("游戏尚未开始，`{0}` 暂不可用；先用 new_game <class> 选择职业（classes 查看可选职业）")]
+    NotInGame(&'static str),
+}
+
+/// `@target` 在 [`CommandArgs`] 中的键
+const TARGET_KEY: &str = "@target";
+
+/// 已通过类型校验的参数
+#[derive(Debug, Default, Clone)]
+pub struct CommandArgs {
+    values: HashMap<&'static str, String>,
+}
+
+impl CommandArgs {
+    /// 取必填参数（或带缺省值的参数）；解析已校验过类型，这里失败说明声明与读取不一致
+    pub fn get<T: FromStr>(&self, name: &str) -> T {
+        self.opt(name)
+            .unwrap_or_else(|| panic!("argument <{name}> is not declared or has wrong type"))
+    }
+
+    /// 取可选参数
+    pub fn opt<T: FromStr>(&self, name: &str) -> Option<T> {
+        self.values.get(name).and_then(|raw| raw.parse().ok())
+    }
+
+    /// 取 `@target`，缺省为玩家
+    pub fn target(&self) -> Target {
+        self.opt(TARGET_KEY).unwrap_or_default()
+    }
+
+    /// 布尔开关是否出现
+    pub fn flag(&self, name: &str) -> bool {
+        self.values.contains_key(name)
+    }
+}
+
+/// 解析后的命令
+#[derive(Debug, Clone)]
+pub struct ParsedCommand {
+    pub name: &'static str,
+    pub kind: CommandType,
+    pub args: CommandArgs,
+}
+
+/// 命令处理器：拿到校验后的参数与 World，通常只负责把参数转换成事件
+pub type CommandHandler = Arc<dyn Fn(&CommandArgs, &mut World) + Send + Sync>;
+
+/// 可由 CLI 直接构造的事件：声明命令格式，并从校验后的参数构造自身
+pub trait CliCommand: Event + Sized {
+    fn spec() -> CommandSpec;
+    fn from_args(args: &CommandArgs) -> Self;
+}
+
+/// 插件注册 CLI 命令的入口
+pub trait CliAppExt {
+    /// 注册命令，解析成功后把 `T` 作为事件发出，由所属插件的系统处理
+    fn register_cli_command<T: CliCommand>(&mut self) -> &mut Self;
+
+    /// 注册需要直接读取 World 的命令（help / status 这类纯查询命令）
+    fn register_cli_handler(
+        &mut self,
+        spec: CommandSpec,
+        handler: impl Fn(&CommandArgs, &mut World) + Send + Sync + 'static,
+    ) -> &mut Self;
+}
+
+impl CliAppExt for App {
+    fn register_cli_command<T: CliCommand>(&mut self) -> &mut Self {
+        self.add_event::<T>();
+        let mut registry = self.world_mut().get_resource_or_init::<CommandRegistry>();
+        registry.register(T::spec(), |args, world| {
+            world.send_event(T::from_args(args));
+        });
+        registry
+            .event_probes
+            .push(|world| !world.resource::<Events<T>>().is_empty());
+        self
+    }
+
+    fn register_cli_handler(
+        &mut self,
+        spec: CommandSpec,
+        handler: impl Fn(&CommandArgs, &mut World) + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.world_mut()
+            .get_resource_or_init::<CommandRegistry>()
+            .register(spec, handler);
+        self
+    }
+}
+
+struct RegisteredCommand {
+    spec: CommandSpec,
+    handler: CommandHandler,
+}
+
+/// 命令注册表
+#[derive(Resource, Default)]
+pub struct CommandRegistry {
+    commands: Vec<RegisteredCommand>,
+    lookup: HashMap<&'static str, usize>,
+    /// 各命令事件队列是否非空（批处理据此判断命令是否已处理完）
+    event_probes: Vec<fn(&World) -> bool>,
+}
+
+impl CommandRegistry {
+    /// 注册命令；重名属于编程错误，直接 panic
+    pub fn register(
+        &mut self,
+        spec: CommandSpec,
+        handler: impl Fn(&CommandArgs, &mut World) + Send + Sync + 'static,
+    ) {
+        let idx = self.commands.len();
+        for key in std::iter::once(spec.name).chain(spec.aliases.iter().copied()) {
+            if self.lookup.insert(key, idx).is_some() {
+                panic!("CLI command `{key}` registered twice");
+            }
+        }
+        self.commands.push(RegisteredCommand {
+            spec,
+            handler: Arc::new(handler),
+        });
+    }
+
+    /// 按名称或别名查找
+    pub fn get(&self, name: &str) -> Option<&CommandSpec> {
+        self.lookup
+            .get(name.to_lowercase().as_str())
+            .map(|&idx| &self.commands[idx].spec)
+    }
+
+    /// 所有命令（注册顺序）
+    pub fn specs(&self) -> impl Iterator<Item = &CommandSpec> {
+        self.commands.iter().map(|c| &c.spec)
+    }
+
+    /// 是否还有尚未被处理的命令事件
+    pub fn has_pending_events(&self, world: &World) -> bool {
+        self.event_probes.iter().any(|probe| probe(world))
+    }
+
+    pub fn handler(&self, name: &str) -> Option<CommandHandler> {
+        self.lookup
+            .get(name)
+            .map(|&idx| self.commands[idx].handler.clone())
+    }
+
+    /// 命令行的分类（未知命令视为系统命令）
+    pub fn command_type(&self, line: &str) -> CommandType {
+        line.split_whitespace()
+            .next()
+            .and_then(|cmd| self.get(cmd))
+            .map_or(CommandType::System, |spec| spec.kind)
+    }
+
+    /// 解析一整行
+    pub fn parse(&self, line: &str) -> Result<ParsedCommand, CliError> {
+        let mut tokens = line.split_whitespace();
+        let cmd = tokens.next().unwrap_or("");
+        let spec = self
+            .get(cmd)
+            .ok_or_else(|| CliError::UnknownCommand(cmd.to_lowercase()))?;
+
+        let mut args = CommandArgs::default();
+        let mut positional = spec.args.iter();
+        let mut tokens = tokens.peekable();
+
+        if let Some(raw) = tokens.next_if(|token| token.starts_with('@')) {
+            if !spec.target {
+                return Err(CliError::UnexpectedArgument {
+                    got: raw.to_string(),
+                    usage: spec.usage(),
+                });
+            }
+            raw.parse::<Target>().map_err(CliError::InvalidTarget)?;
+            args.values.insert(TARGET_KEY, raw.to_string());
+        }
+
+        while let Some(token) = tokens.next() {
+            if let Some(flag_name) = token.strip_prefix("--") {
+                let flag = spec
+                    .flags
+                    .iter()
+                    .find(|f| f.name == flag_name)
+                    .ok_or_else(|| CliError::UnknownFlag {
+                        flag: flag_name.to_string(),
+                        usage: spec.usage(),
+                    })?;
+                let value = match flag.value {
+                    None => "true".to_string(),
+                    Some(kind) => {
+                        let raw = tokens.next().ok_or_else(|| CliError::MissingFlag {
+                            flag: flag.name,
+                            usage: spec.usage(),
+                        })?;
+                        check(flag.name, kind, raw)?
+                    }
+                };
+                args.values.insert(flag.name, value);
+                continue;
+            }
+
+            let arg = positional
+                .next()
+                .ok_or_else(|| CliError::UnexpectedArgument {
+                    got: token.to_string(),
+                    usage: spec.usage(),
+                })?;
+            args.values
+                .insert(arg.name, check(arg.name, arg.kind, token)?);
+        }
+
+        for arg in positional {
+            match arg.default {
+                Some(default) => {
+                    args.values.insert(arg.name, default.to_string());
+                }
+                None if arg.required => {
+                    return Err(CliError::MissingArgument {
+                        arg: arg.name,
+                        usage: spec.usage(),
+                    });
+                }
+                None => {}
+            }
+        }
+
+        if let Some(flag) = spec
+            .flags
+            .iter()
+            .find(|f| f.required && !args.values.contains_key(f.name))
+        {
+            return Err(CliError::MissingFlag {
+                flag: flag.name,
+                usage: spec.usage(),
+            });
+        }
+
+        Ok(ParsedCommand {
+            name: spec.name,
+            kind: spec.kind,
+            args,
+        })
+    }
+
+    /// 解析一整行，并检查当前阶段能否执行：`in_game` 为 false 时只接受 `pre_game` 命令
+    pub fn parse_in(&self, line: &str, in_game: bool) -> Result<ParsedCommand, CliError> {
+        let command = self.parse(line)?;
+        let pre_game = self.get(command.name).is_some_and(|spec| spec.pre_game);
+        if in_game || pre_game {
+            Ok(command)
+        } else {
+            Err(CliError::NotInGame(command.name))
+        }
+    }
+
+    /// `help` 的命令列表：系统命令在前，同类按注册顺序
+    pub fn help(&self) -> String {
+        let mut out = String::from("命令列表:\n");
+        for kind in [CommandType::System, CommandType::Game] {
+            for spec in self.specs().filter(|spec| spec.kind == kind) {
+                writeln!(out, "  {:<28} {}", spec.usage(), spec.summary).unwrap();
+            }
+        }
+        out.push_str("  help <command> 查看单条命令的参数说明\n");
+        out.push_str(&"-".repeat(65));
+        out
+    }
+}
+
+fn check(name: &'static str, kind: ArgKind, raw: &str) -> Result<String, CliError> {
+    if kind.accepts(raw) {
+        return Ok(raw.to_string());
+    }
+
+    // 非数字时只说“需要整数”，是数字但为负 / 越界时才强调非负
+    let expected = match kind {
+        ArgKind::UInt if raw.parse::<i64>().is_err() => ArgKind::Int.describe(),
+        _ => kind.describe(),
+    };
+    Err(CliError::InvalidArgument {
+        arg: name,
+        expected,
+        got: raw.to_string(),
+    })
+}
@@ -1,16 +1,26 @@
 //! 文字 CLI：读取 stdin → 解析命令 → 执行并打印
 
 use bevy::app::AppExit;
+use bevy::ecs::event::EventCursor;
 use bevy::prelude::*;
 use once_cell::sync::Lazy;
 use std::collections::VecDeque;
-use std::num::NonZero;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
-use uuid::Uuid;
 use std::io::{self, Write};
 
-use crate::core::{events::LogEvent, states::AppState};
-use crate::data::{ItemAssets, schema::ItemList};
+use super::command::{
+    ArgKind, ArgSpec, CliAppExt, CliError, CommandArgs, CommandRegistry, CommandSpec,
+    CommandType,
+};
+use crate::core::{
+    args::LaunchArgs,
+    events::LogEvent,
+    resources::GameTurn,
+    rng::GameRng,
+    states::{AppState, accepts_input},
+};
+use crate::data::{progression::Progression, registry::ItemRegistry};
 use crate::equipment::components::Equipment;
 use crate::inventory::components::Backpack;
 use crate::character::components::{Player, Stats};
@@ -27,6 +37,8 @@ static GAME_LOG_HISTORY: Lazy<Arc<Mutex<VecDeque<GameLogEntry>>>> =
 static CURRENT_GAME_ENTRY: Lazy<Arc<Mutex<Option<GameLogEntry>>>> =
     Lazy::new(|| Arc::new(Mutex::new(None)));
 
+static PLAIN_OUTPUT: AtomicBool = AtomicBool::new(false);
+
 static UI_STATE: Lazy<Arc<Mutex<UIState>>> = 
     Lazy::new(|| Arc::new(Mutex::new(UIState::default())));
 
@@ -35,8 +47,6 @@ struct UIState {
     last_stats_hash: u64,
     show_status_bar: bool,
     needs_refresh: bool,
-    current_input: String,
-    cursor_position: usize,
 }
 
 /// 游戏日志条目
@@ -46,16 +56,10 @@ struct GameLogEntry {
     outputs: Vec<String>,   // 对应的输出内容
 }
 
-/// 命令类型分类
-#[derive(Debug, Clone, PartialEq)]
-enum CommandType {
-    System,    // 系统命令：help, status, items, inventory等
-    Game,      // 游戏命令：gain_exp, take_damage, heal, equip, use等
-}
-
 /// CLI消息类型
 #[derive(Clone)]
-enum CliMessage {
+#[allow(dead_code)] // SystemResponse / Success / Warning 为预留的消息级别
+pub(super) enum CliMessage {
     UserInput(String, CommandType),    // 用户输入的命令及其类型
     SystemResponse(String),            // 系统命令的即时响应
     GameLog(String),                  // 游戏日志条目
@@ -69,97 +73,82 @@ enum CliMessage {
 pub struct DebugCliPlugin;
 impl Plugin for DebugCliPlugin {
     fn build(&self, app: &mut App) {
-        {
+        let args = app
+            .world()
+            .get_resource::<LaunchArgs>()
+            .cloned()
+            .unwrap_or_default();
+
+        if let Some(source) = args.script {
+            // 批处理：脚本逐行喂入，纯文本 transcript，结束后退出
+            super::batch::build(app, source);
+        } else if let Some(path) = args.replay {
+            // 回放：按录制的 tick 喂入命令，结束后报告是否一致
+            super::replay::build(app, path, args.verify);
+        } else {
             let buffer = CLI_BUFFER.clone();
             std::thread::spawn(move || {
                 use std::io::{self, BufRead};
                 let stdin = io::stdin();
-                for line_result in stdin.lock().lines() {
-                    if let Ok(line) = line_result {
-                        let line = line.trim();
-                        if !line.is_empty() {
-                            let mut buf = buffer.lock().unwrap();
-                            buf.push_back(line.to_string());
-                        }
+                for line in stdin.lock().lines().map_while(Result::ok) {
+                    let line = line.trim();
+                    if !line.is_empty() {
+                        let mut buf = buffer.lock().unwrap();
+                        buf.push_back(line.to_string());
                     }
                 }
             });
+            app
+                // 每帧从 buffer 取出所有命令行写入事件
+                .add_systems(Update, read_stdin.before(log_cli_input))
+                // UI渲染系统
+                .add_systems(
+                    Update,
+                    render_ui
+                        .run_if(accepts_input)
+                        .after(log_cli_input)
+                        .before(execute_cli_lines),
+                )
+                // 游戏开始时初始化UI
+                .add_systems(OnEnter(AppState::InGame), initialize_ui);
         }
+
+        if let Some(path) = args.record {
+            super::replay::record(app, path);
+        }
+
+        register_builtin_commands(app);
+
         app
+            // 命令表（功能插件可能已先行注册命令）
+            .init_resource::<CommandRegistry>()
             // 事件：原始输入行
             .add_event::<CliLine>()
-            // 每帧从 buffer 取出所有命令行写入事件
-            .add_systems(Update, read_stdin)
             // log_cli_input: 在读取输入后立即记录命令
-            .add_systems(Update, log_cli_input.run_if(in_state(AppState::InGame)).after(read_stdin))
-            // UI渲染系统
-            .add_systems(Update, render_ui.run_if(in_state(AppState::InGame)).after(log_cli_input))
-            // 命令执行，确保在日志和 UI 之后（即实际执行逻辑与打印解耦）
+            .add_systems(Update, log_cli_input.run_if(accepts_input))
+            // 命令执行，确保在日志之后（即实际执行逻辑与打印解耦）
             .add_systems(
                 Update,
-                (
-                    execute_basic_commands,
-                    execute_character_commands,
-                )
-                    .run_if(in_state(AppState::InGame))
-                    .after(render_ui),
-            )
-            // 游戏开始时初始化UI
-            .add_systems(OnEnter(AppState::InGame), initialize_ui);
+                execute_cli_lines
+                    .run_if(accepts_input)
+                    .after(log_cli_input),
+            );
+    }
+}
+
 /// 日志记录：在读取到每一行命令时，立即入队 UserInput（保证顺序）
-fn log_cli_input(mut line_reader: EventReader<CliLine>) {
+pub(super) fn log_cli_input(mut line_reader: EventReader<CliLine>, registry: Res<CommandRegistry>) {
     for CliLine(input) in line_reader.read() {
-        let command = parse_command(&input);
-        let ty = command.command_type();
+        let ty = registry.command_type(input);
         queue_output(CliMessage::UserInput(input.clone(), ty));
     }
 }
-    }
-}
 
-/* ---------------------------- 事件与枚举 ---------------------------- */
+/* ---------------------------- 事件 ---------------------------- */
 
 /// 终端敲的一整行
 #[derive(Event)]
-struct CliLine(String);
-
-/// 我们支持的命令
-enum Command {
-    // 系统命令
-    Help,
-    Status,
-    Exit,
-    Items(Option<String>), // None=全部；Some(token)=按 id/uuid/name 查询
-    Inventory,
-    Stats,
-
-    // 游戏命令
-    Give { id: String, count: u32 },
-    Equip { slot: String, index: usize },
-    Unequip { slot: String },
-    Use { index: usize },
-    GainExp { amount: i32 },
-    TakeDamage { damage: i32 },
-    Heal { amount: i32 },
-
-    Unsupported(String),
-}
-
-impl Command {
-    /// 获取命令类型
-    fn command_type(&self) -> CommandType {
-        match self {
-            Command::Help | Command::Status | Command::Exit |
-            Command::Items(_) | Command::Inventory | Command::Stats => CommandType::System,
-
-            Command::Give { .. } | Command::Equip { .. } | Command::Unequip { .. } |
-            Command::Use { .. } | Command::GainExp { .. } | Command::TakeDamage { .. } |
-            Command::Heal { .. } => CommandType::Game,
-
-            Command::Unsupported(_) => CommandType::System,
-        }
-    }
-}
+pub struct CliLine(pub String);
 
 /* ---------------------------- 读取 stdin ---------------------------- */
 
@@ -172,270 +161,192 @@ fn read_stdin(mut writer: EventWriter<CliLine>) {
 
 /* ---------------------------- 命令执行 ---------------------------- */
 
-fn execute_basic_commands(
-    mut line_reader: EventReader<CliLine>,
-    mut app_exit: EventWriter<AppExit>,
-    mut log: EventWriter<LogEvent>,
-    state: Res<State<AppState>>,
-    item_assets: Res<ItemAssets>,
-    lists: Res<Assets<ItemList>>,
-    backpack: Res<Backpack>,
-    equipment: Res<Equipment>,
-    _player_query: Query<&Stats, With<Player>>,
-    mut ev_give: EventWriter<crate::inventory::events::GiveItemEvent>,
-    mut ev_list: EventWriter<crate::inventory::events::ListInventoryEvent>,
-    mut ev_equip: EventWriter<crate::equipment::events::EquipEvent>,
-    mut ev_unequip: EventWriter<crate::equipment::events::UnequipEvent>,
-    mut ev_use: EventWriter<crate::inventory::events::UseItemEvent>,
-) {
-    for CliLine(input) in line_reader.read() {
-        let command = parse_command(input);
-        // let command_type = command.command_type();
-        // queue_output(CliMessage::UserInput(input.clone(), command_type.clone()));
-        match command {
-            Command::Help => {
-                log.write(LogEvent(
-                    "命令列表:
-  help                   查看帮助
-  status                 查看当前状态
-  exit / quit            退出程序
-  items                  列出所有物品
-  items <token>          用 id / uuid / 名称 查询单个物品
-  give <id> <count>      给予物品
-  inventory              查看物品栏
-  equip <slot> <index>   装备物品 (slot: head/body/weapon/accessory)
-  unequip <slot>         卸下装备
-  use <index>            使用物品
-  stats                  查看角色属性
-  gain_exp <amount>      获得经验 (调试用)
-  take_damage <damage>   受到伤害 (调试用)
-  heal <amount>          恢复生命值 (调试用)
------------------------------------------------------------------
-  "
-                    .into(),
-                ));
-            }
-
-            Command::Status => {
-                let cnt = item_assets
-                    .handle
-                    .as_ref()
-                    .and_then(|h| lists.get(h))
-                    .map_or(0, |list| list.items.len());
-                log.write(LogEvent(format!(
-                    "State: {:?}, Items Loaded: {}",
-                    state.get(),
-                    cnt
-                )));
-
-                log.write(LogEvent("--- Equipment ---".into()));
-                if let Some(weapon) = &equipment.weapon {
-                    log.write(LogEvent(format!(
-                        "Weapon: {} (id={})",
-                        weapon.proto.name, weapon.proto.id
-                    )));
-                } else {
-                    log.write(LogEvent("Weapon: (empty)".into()));
+/// 解析每一行并交给注册的处理器；解析失败直接输出错误。
+/// 职业选择阶段只执行声明了 `pre_game` 的命令
+pub(super) fn execute_cli_lines(world: &mut World, mut cursor: Local<EventCursor<CliLine>>) {
+    let lines: Vec<String> = cursor
+        .read(world.resource::<Events<CliLine>>())
+        .map(|CliLine(line)| line.clone())
+        .collect();
+    let in_game = *world.resource::<State<AppState>>().get() == AppState::InGame;
+
+    for line in lines {
+        let registry = world.resource::<CommandRegistry>();
+        match registry.parse_in(&line, in_game) {
+            Ok(command) => {
+                let handler = registry
+                    .handler(command.name)
+                    .expect("parsed command must have a handler");
+                handler(&command.args, world);
+                if command.kind == CommandType::Game {
+                    world.resource_mut::<GameTurn>().0 += 1;
                 }
-
-                log.write(LogEvent("--- Backpack ---".into()));
-                let mut empty = true;
-                for (i, stack) in backpack.slots.iter().enumerate() {
-                    if stack.count > 0 {
-                        empty = false;
-                        log.write(LogEvent(format!(
-                            "[{}] {} ×{} (id={})",
-                            i, stack.proto.name, stack.count, stack.proto.id
-                        )));
-                    }
-                }
-                if empty {
-                    log.write(LogEvent("  (empty)".into()));
-                }
-            }
-
-            Command::Exit => {
-                log.write(LogEvent("Bye~".into()));
-                app_exit.write(AppExit::Error(NonZero::<u8>::MIN));
-            }
-
-            Command::Items(token) => {
-                if let Some(handle) = &item_assets.handle {
-                    if let Some(list) = lists.get(handle) {
-                        match token {
-                            None => {
-                                // 全部列出
-                                for entry in &list.items {
-                                    let uuid = uuid_from_id(&entry.id);
-                                    log.write(LogEvent(format!(
-                                        "{} | {} | {}",
-                                        uuid, entry.id, entry.name
-                                    )));
-                                }
-                            }
-                            Some(t) => {
-                                // 按三种字段模糊匹配
-                                let t_low = t.to_lowercase();
-                                if let Some(e) = list.items.iter().find(|e| {
-                                    e.id.eq_ignore_ascii_case(&t_low)
-                                        || e.name.eq_ignore_ascii_case(&t_low)
-                                        || uuid_from_id(&e.id).to_string() == t_low
-                                }) {
-                                    let uuid = uuid_from_id(&e.id);
-                                    log.write(LogEvent(format!(
-                                        "==================================================
-UUID : {uuid}
-ID   : {}
-Name : {}
-Atk  : {}
-Heal : {}
-==================================================",
-                                        e.id, e.name, e.atk, e.heal
-                                    )));
-                                } else {
-                                    log.write(LogEvent("未找到匹配物品".into()));
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-
-            Command::Give { id, count } => {
-                ev_give.write(crate::inventory::events::GiveItemEvent { id, count });
-            }
-
-            Command::Inventory => {
-                ev_list.write(crate::inventory::events::ListInventoryEvent);
-            }
-
-            Command::Equip { slot, index } => {
-                ev_equip.write(crate::equipment::events::EquipEvent { slot, index });
-            }
-
-            Command::Unequip { slot } => {
-                ev_unequip.write(crate::equipment::events::UnequipEvent { slot });
-            }
-
-            Command::Use { index } => {
-                ev_use.write(crate::inventory::events::UseItemEvent { index });
-            }
-
-            // Character 相关命令在 execute_character_commands 中处理
-            Command::Stats
-            | Command::GainExp { .. }
-            | Command::TakeDamage { .. }
-            | Command::Heal { .. } => {
-                // 这些命令由 execute_character_commands 处理
-            }
-
-            Command::Unsupported(cmd) => {
-                log.write(LogEvent(format!("不支持的命令: {cmd}")));
             }
+            Err(err) => queue_output(CliMessage::Error(err.to_string())),
         }
     }
 }
 
-/// 处理 Character 相关命令
-fn execute_character_commands(
-    mut line_reader: EventReader<CliLine>,
-    _player_query: Query<&Stats, With<Player>>,
-    mut ev_show_stats: EventWriter<crate::character::events::ShowStats>,
-    mut ev_gain_exp: EventWriter<crate::character::events::GainExp>,
-    mut ev_take_damage: EventWriter<crate::character::events::TakeDamage>,
-    mut ev_heal: EventWriter<crate::character::events::Heal>,
-) {
-    for CliLine(input) in line_reader.read() {
-        match parse_command(input) {
-            Command::Stats => {
-                ev_show_stats.write(crate::character::events::ShowStats { entity: None });
-            }
-
-            Command::GainExp { amount } => {
-                ev_gain_exp.write(crate::character::events::GainExp {
-                    entity: Entity::PLACEHOLDER, // 系统会自动查找玩家
-                    amount,
-                });
-            }
+/* ---------------------------- 内置命令 ---------------------------- */
 
-            Command::TakeDamage { damage } => {
-                ev_take_damage.write(crate::character::events::TakeDamage {
-                    entity: Entity::PLACEHOLDER, // 系统会自动查找玩家
-                    damage,
-                });
-            }
+/// CLI 自身的查询命令；游戏命令由各功能插件通过 `register_cli_command` 注册
+fn register_builtin_commands(app: &mut App) {
+    use ArgKind::*;
+    use CommandType::*;
 
-            Command::Heal { amount } => {
-                ev_heal.write(crate::character::events::Heal {
-                    entity: Entity::PLACEHOLDER, // 系统会自动查找玩家
-                    amount,
-                });
-            }
+    app.register_cli_handler(
+        CommandSpec::new("help", System, "查看帮助")
+            .pre_game()
+            .alias("h")
+            .alias("?")
+            .arg(ArgSpec::optional("command", Word, "要查看说明的命令")),
+        cmd_help,
+    )
+    .register_cli_handler(
+        CommandSpec::new("status", System, "查看当前状态")
+            .pre_game()
+            .alias("s"),
+        cmd_status,
+    )
+    .register_cli_handler(
+        CommandSpec::new("exit", System, "退出程序")
+            .pre_game()
+            .alias("quit")
+            .alias("q"),
+        |_, world| {
+            world.send_event(LogEvent("Bye~".into()));
+            world.send_event(AppExit::Success);
+        },
+    )
+    .register_cli_handler(
+        CommandSpec::new("items", System, "列出所有物品；带参数时按 id / uuid / 名称查询，或按前缀模糊搜索")
+            .pre_game()
+            .alias("item")
+            .alias("i")
+            .arg(ArgSpec::optional("token", Word, "物品 id / uuid / 名称")),
+        cmd_items,
+    );
+}
 
-            // 其他命令忽略
-            _ => {}
+fn cmd_help(args: &CommandArgs, world: &mut World) {
+    let registry = world.resource::<CommandRegistry>();
+    let text = match args.opt::<String>("command") {
+        None => Ok(registry.help()),
+        Some(name) => registry
+            .get(&name)
+            .map(CommandSpec::help)
+            .ok_or(CliError::UnknownCommand(name)),
+    };
+    match text {
+        Ok(text) => {
+            world.send_event(LogEvent(text));
         }
+        Err(err) => queue_output(CliMessage::Error(err.to_string())),
     }
 }
 
-/* ---------------------------- 工具函数 ---------------------------- */
-
-fn parse_command(input: &str) -> Command {
-    let mut parts = input.split_whitespace();
-    let cmd = parts.next().unwrap_or("").to_lowercase();
-    match cmd.as_str() {
-        "help" | "h" | "?" => Command::Help,
-        "status" | "s" => Command::Status,
-        "exit" | "quit" | "q" => Command::Exit,
-        "items" | "item" | "i" => {
-            let token = parts.next().map(|s| s.to_string());
-            Command::Items(token)
-        }
-        "give" => {
-            let id = parts.next().unwrap_or("").to_string();
-            let cnt = parts.next().unwrap_or("1").parse().unwrap_or(1);
-            Command::Give { id, count: cnt }
+fn cmd_status(_: &CommandArgs, world: &mut World) {
+    let mut lines = Vec::new();
+
+    let registry = world.resource::<ItemRegistry>();
+    lines.push(format!(
+        "State: {:?}, Items Loaded: {}, Seed: {}",
+        world.resource::<State<AppState>>().get(),
+        registry.len(),
+        world.resource::<GameRng>().seed()
+    ));
+
+    let mut player = world.query_filtered::<(&Equipment, &Backpack), With<Player>>();
+    if let Ok((equipment, backpack)) = player.single(world) {
+        let registry = world.resource::<ItemRegistry>();
+        lines.push("--- Equipment ---".into());
+        if let Some(weapon) = &equipment.weapon {
+            let weapon = registry.get(weapon.item);
+            lines.push(format!("Weapon: {} (id={})", weapon.name, weapon.id));
+        } else {
+            lines.push("Weapon: (empty)".into());
         }
-        "inventory" | "inv" => Command::Inventory,
-        "equip" => {
-            let slot = parts.next().unwrap_or("").to_string();
-            let idx = parts.next().unwrap_or("0").parse().unwrap_or(0);
-            Command::Equip { slot, index: idx }
-        }
-        "unequip" => {
-            let slot = parts.next().unwrap_or("").to_string();
-            Command::Unequip { slot }
-        }
-        "use" => {
-            let idx = parts.next().unwrap_or("0").parse().unwrap_or(0);
-            Command::Use { index: idx }
+
+        lines.push("--- Backpack ---".into());
+        let mut empty = true;
+        for (i, stack) in backpack.slots.iter().enumerate() {
+            if stack.count > 0 {
+                empty = false;
+                let item = registry.get(stack.item);
+                lines.push(format!(
+                    "[{}] {} ×{} (id={})",
+                    i, item.name, stack.count, item.id
+                ));
+            }
         }
-        "stats" => Command::Stats,
-        "gain_exp" => {
-            let amount = parts.next().unwrap_or("0").parse().unwrap_or(0);
-            Command::GainExp { amount }
+        if empty {
+            lines.push("  (empty)".into());
         }
-        "take_damage" => {
-            let damage = parts.next().unwrap_or("0").parse().unwrap_or(0);
-            Command::TakeDamage { damage }
+    }
+
+    for line in lines {
+        world.send_event(LogEvent(line));
+    }
+}
+
+fn cmd_items(args: &CommandArgs, world: &mut World) {
+    let registry = world.resource::<ItemRegistry>();
+
+    let mut lines = Vec::new();
+    match args.opt::<String>("token") {
+        None => {
+            // 全部列出
+            for (item, entry) in registry.iter() {
+                lines.push(format!("{} | {} | {}", registry.uuid(item), entry.id, entry.name));
+            }
         }
-        "heal" => {
-            let amount = parts.next().unwrap_or("0").parse().unwrap_or(0);
-            Command::Heal { amount }
+        Some(t) => {
+            // 先按 id / uuid / 名称精确查找，再按前缀 / 模糊搜索
+            let hits = match registry.lookup(&t) {
+                Some(item) => vec![item],
+                None => registry.search(&t),
+            };
+            match hits.as_slice() {
+                [] => lines.push("未找到匹配物品".into()),
+                [item] => {
+                    let e = registry.get(*item);
+                    lines.push(format!(
+                        "==================================================
+UUID : {}
+ID   : {}
+Name : {}
+Kind : {}
+Atk  : {}
+Def  : {}
+==================================================",
+                        registry.uuid(*item), e.id, e.name, e.kind, e.atk, e.def
+                    ));
+                }
+                _ => {
+                    lines.push(format!("匹配 {} 个物品：", hits.len()));
+                    for item in &hits {
+                        let e = registry.get(*item);
+                        lines.push(format!("  {} | {}", e.id, e.name));
+                    }
+                }
+            }
         }
-        other => Command::Unsupported(other.into()),
     }
-}
 
-fn uuid_from_id(id: &str) -> Uuid {
-    // 用固定 namespace + id 字节生成版本 5 UUID，保证可重复得到同一值
-    Uuid::new_v5(&Uuid::NAMESPACE_OID, id.as_bytes())
+    for line in lines {
+        world.send_event(LogEvent(line));
+    }
 }
 
 /* ---------------------------- UI 系统 ---------------------------- */
 
 /// 队列输出消息
-fn queue_output(message: CliMessage) {
+pub(super) fn queue_output(message: CliMessage) {
+    if PLAIN_OUTPUT.load(Ordering::Relaxed) {
+        queue_plain_output(message);
+        return;
+    }
+
     let mut outputs = PENDING_OUTPUTS.lock().unwrap();
     let mut game_history = GAME_LOG_HISTORY.lock().unwrap();
     let mut current_entry = CURRENT_GAME_ENTRY.lock().unwrap();
@@ -516,6 +427,30 @@ fn queue_output(message: CliMessage) {
     }
 }
 
+/// 纯文本输出（批处理 transcript）：无边框、无 ANSI 转义，也不维护游戏日志历史
+fn queue_plain_output(message: CliMessage) {
+    let line = match message {
+        CliMessage::UserInput(cmd, _) => format!("> {cmd}"),
+        CliMessage::SystemResponse(msg)
+        | CliMessage::GameLog(msg)
+        | CliMessage::Info(msg)
+        | CliMessage::Success(msg)
+        | CliMessage::Warning(msg)
+        | CliMessage::Error(msg) => msg,
+    };
+    PENDING_OUTPUTS.lock().unwrap().push_back(line);
+}
+
+/// 切换为纯文本输出
+pub(super) fn set_plain_output(plain: bool) {
+    PLAIN_OUTPUT.store(plain, Ordering::Relaxed);
+}
+
+/// 取出所有待输出的消息
+pub(super) fn drain_outputs() -> Vec<String> {
+    PENDING_OUTPUTS.lock().unwrap().drain(..).collect()
+}
+
 /// 公开函数：供main.rs调用，将LogEvent转换为格式化消息
 pub fn queue_log_message(message: String) {
     queue_output(CliMessage::Info(message));
@@ -527,7 +462,7 @@ pub fn queue_game_log(message: String) {
 }
 
 /// 主UI渲染系统
-fn render_ui(player_query: Query<&Stats, With<Player>>) {
+fn render_ui(player_query: Query<&Stats, With<Player>>, progression: Res<Progression>) {
     let mut ui_state = UI_STATE.lock().unwrap();
     let mut outputs = PENDING_OUTPUTS.lock().unwrap();
     let game_history = GAME_LOG_HISTORY.lock().unwrap();
@@ -576,7 +511,7 @@ fn render_ui(player_query: Query<&Stats, With<Player>>) {
                 }
 
                 // 显示当前正在进行的条目
-                if let Some(ref entry) = current_entry.as_ref() {
+                if let Some(entry) = current_entry.as_ref() {
                     display_game_log_entry(entry);
                 }
 
@@ -585,7 +520,7 @@ fn render_ui(player_query: Query<&Stats, With<Player>>) {
             
             // 显示状态栏
             if let Ok(stats) = player_query.single() {
-                let status_bar = format_status_bar(stats);
+                let status_bar = format_status_bar(stats, &progression);
                 println!("{}", status_bar);
             }
             
@@ -623,11 +558,11 @@ fn display_input_area() {
 }
 
 /// 格式化状态栏
-fn format_status_bar(stats: &Stats) -> String {
+fn format_status_bar(stats: &Stats, progression: &Progression) -> String {
     format!(
-        "📊 [HP:{}/{} ⚔️ATK:{} 🛡️DEF:{} 📈LV:{}({}/{})]",
+        "📊 [HP:{}/{} ⚔️ATK:{} 🛡️DEF:{} 📈LV:{}({})]",
         stats.hp, stats.max_hp, stats.atk, stats.def, 
-        stats.lv, stats.exp, stats.exp_to_next()
+        stats.lv, stats.exp_label(progression)
     )
 }
 
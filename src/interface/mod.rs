@@ -1 +1,4 @@
+pub mod command;
 pub mod debug_cli;
+mod batch;
+pub mod replay;
@@ -0,0 +1,415 @@
+//! 录制与回放：复现 bug 报告
+//!
+//! `--record <file>` 把每条被接受的命令连同 tick（进入 `InGame` 后的帧数）与执行前的
+//! 状态哈希写入回放文件，文件头记录随机数种子、所选职业与各数据文件的内容哈希；每条命令后都会
+//! 重写文件，进程崩溃时也保留到最后一条命令。
+//!
+//! `--replay <file>` 用同一种子与职业启动，在录制时的 tick 把命令重新喂入（上一条命令的事件链
+//! 处理完之前不会喂下一条，避免系统执行顺序差异造成误报），加 `--verify` 时逐条核对
+//! 状态哈希并报告第一次分歧。
+//!
+//! 退出码：0 = 回放完成（且一致）；2 = 回放文件 / transcript 无法读写，或无法以录制时的
+//! 职业开始；4 = `--verify` 发现分歧
+
+use bevy::app::AppExit;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::num::NonZero;
+use std::path::{Path, PathBuf};
+
+use super::batch::{self, CliIdle, CliScript};
+use super::command::CommandRegistry;
+use super::debug_cli::{CliLine, CliMessage, execute_cli_lines, log_cli_input, queue_output};
+use crate::character::components::ChosenClass;
+use crate::core::args::LaunchArgs;
+use crate::core::{
+    hash::fnv1a_hex,
+    rng::GameRng,
+    states::{AppState, accepts_input},
+};
+use crate::data::{ItemAssets, registry::ItemRegistry, schema::ItemList};
+use crate::save::systems::{ActorQuery, capture_save, write_json};
+
+/// 当前回放文件格式版本（v2 起记录职业）
+pub const REPLAY_VERSION: u32 = 2;
+
+/// v1 回放录制于没有职业选择的版本，玩家即以冒险者的属性开局
+const V1_REPLAY_CLASS: &str = "adventurer";
+
+/// `--verify` 发现分歧时的退出码
+pub const REPLAY_DIVERGED_EXIT_CODE: u8 = 4;
+
+/// 录制没有正常结束（没有 `final_tick`）时，最后一条命令之后再运行的帧数
+const REPLAY_TAIL_TICKS: u64 = 60;
+
+/// 回放文件
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ReplayFile {
+    pub version: u32,
+    pub seed: u64,
+    /// 录制时选择的职业；数据没有职业时为空
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub class: Option<String>,
+    /// 录制时加载的物品文件，按清单顺序
+    pub data: Vec<DataHash>,
+    pub commands: Vec<RecordedCommand>,
+    /// 正常退出时的 tick 与状态哈希；崩溃时没有
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub final_tick: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub final_state: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DataHash {
+    pub path: String,
+    pub hash: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RecordedCommand {
+    pub tick: u64,
+    pub line: String,
+    /// 执行这条命令之前的状态哈希
+    pub state: String,
+}
+
+impl ReplayFile {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            version: REPLAY_VERSION,
+            seed,
+            class: None,
+            data: Vec::new(),
+            commands: Vec::new(),
+            final_tick: None,
+            final_state: None,
+        }
+    }
+
+    pub fn read(path: &Path) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path).map_err(|err| err.to_string())?;
+        let mut file: Self =
+            serde_json::from_str(&text).map_err(|err| format!("格式错误: {err}"))?;
+        if file.version == 1 {
+            file.version = REPLAY_VERSION;
+            file.class = Some(V1_REPLAY_CLASS.into());
+        }
+        if file.version != REPLAY_VERSION {
+            return Err(format!(
+                "回放文件版本 {} 不受支持（当前版本 {REPLAY_VERSION}）",
+                file.version
+            ));
+        }
+        Ok(file)
+    }
+}
+
+/// 进入 `InGame` 后经过的帧数
+#[derive(Resource, Debug, Default)]
+pub struct ReplayClock(pub u64);
+
+fn advance_clock(mut clock: ResMut<ReplayClock>) {
+    clock.0 += 1;
+}
+
+/// 计算状态哈希所需的参数：全部角色 + 随机数状态
+type StateParams<'w, 's> = (ActorQuery<'w, 's>, Res<'w, ItemRegistry>, Res<'w, GameRng>);
+
+/// 状态哈希：与存档内容相同（角色属性、背包、装备、随机数）
+fn state_hash((actors, registry, rng): &StateParams) -> String {
+    let save = capture_save(actors, registry, rng);
+    fnv1a_hex(&serde_json::to_vec(&save).unwrap_or_default())
+}
+
+fn data_hashes(assets: &ItemAssets, lists: &Assets<ItemList>) -> Vec<DataHash> {
+    assets
+        .file_hashes(lists)
+        .into_iter()
+        .map(|(path, hash)| DataHash { path, hash })
+        .collect()
+}
+
+/* ---------------------------- 录制 ---------------------------- */
+
+#[derive(Resource)]
+struct Recorder {
+    path: PathBuf,
+    file: ReplayFile,
+    /// 写入失败只提示一次
+    failed: bool,
+}
+
+impl Recorder {
+    fn write(&mut self) {
+        if let Err(err) = write_json(&self.path, &self.file)
+            && !self.failed
+        {
+            self.failed = true;
+            queue_output(CliMessage::Error(format!(
+                "无法写入回放文件 {}: {err}",
+                self.path.display()
+            )));
+        }
+    }
+}
+
+pub(super) fn record(app: &mut App, path: PathBuf) {
+    let seed = app.world().resource::<GameRng>().seed();
+    app.insert_resource(Recorder {
+        path,
+        file: ReplayFile::new(seed),
+        failed: false,
+    })
+    .init_resource::<ReplayClock>()
+    .add_systems(OnEnter(AppState::InGame), record_header)
+    .add_systems(
+        Update,
+        record_commands
+            .run_if(in_state(AppState::InGame))
+            .after(log_cli_input)
+            .before(execute_cli_lines),
+    )
+    .add_systems(
+        Last,
+        (finish_recording, advance_clock.run_if(in_state(AppState::InGame))).chain(),
+    );
+}
+
+/// 进入游戏时写入文件头：所选职业与数据文件哈希
+fn record_header(
+    mut recorder: ResMut<Recorder>,
+    chosen: Option<Res<ChosenClass>>,
+    assets: Res<ItemAssets>,
+    lists: Res<Assets<ItemList>>,
+) {
+    recorder.file.class = chosen.map(|chosen| chosen.0.clone());
+    recorder.file.data = data_hashes(&assets, &lists);
+    recorder.write();
+}
+
+/// 只录制能解析的命令；在命令执行之前计算状态哈希
+fn record_commands(
+    mut lines: EventReader<CliLine>,
+    commands: Res<CommandRegistry>,
+    clock: Res<ReplayClock>,
+    mut recorder: ResMut<Recorder>,
+    state: StateParams,
+) {
+    let accepted: Vec<String> = lines
+        .read()
+        .filter(|CliLine(line)| commands.parse(line).is_ok())
+        .map(|CliLine(line)| line.clone())
+        .collect();
+    if accepted.is_empty() {
+        return;
+    }
+
+    let hash = state_hash(&state);
+    for line in accepted {
+        recorder.file.commands.push(RecordedCommand {
+            tick: clock.0,
+            line,
+            state: hash.clone(),
+        });
+    }
+    recorder.write();
+}
+
+fn finish_recording(
+    mut exits: EventReader<AppExit>,
+    clock: Res<ReplayClock>,
+    mut recorder: ResMut<Recorder>,
+    state: StateParams,
+) {
+    if exits.read().count() == 0 {
+        return;
+    }
+    recorder.file.final_tick = Some(clock.0);
+    recorder.file.final_state = Some(state_hash(&state));
+    recorder.write();
+}
+
+/* ---------------------------- 回放 ---------------------------- */
+
+#[derive(Resource)]
+struct Replayer {
+    file: ReplayFile,
+    next: usize,
+    verify: bool,
+    divergence: Option<String>,
+    finished: bool,
+}
+
+pub(super) fn build(app: &mut App, path: PathBuf, verify: bool) {
+    let (file, io_error) = match ReplayFile::read(&path) {
+        Ok(file) => (file, false),
+        Err(err) => {
+            eprintln!("无法读取回放文件 {}: {err}", path.display());
+            (ReplayFile::new(0), true)
+        }
+    };
+
+    // 覆盖 CorePlugin 按 --seed 创建的随机数，并跳过职业选择
+    app.insert_resource(GameRng::new(file.seed));
+    if let Some(mut args) = app.world_mut().get_resource_mut::<LaunchArgs>() {
+        args.class = file.class.clone();
+    }
+    batch::install(app, VecDeque::new(), io_error);
+    app.insert_resource(Replayer {
+        file,
+        next: 0,
+        verify,
+        divergence: None,
+        finished: false,
+    })
+    .init_resource::<ReplayClock>()
+    .add_systems(OnEnter(AppState::InGame), check_data_hashes)
+    .add_systems(
+        Update,
+        feed_replay
+            .run_if(accepts_input)
+            .before(log_cli_input),
+    )
+    .add_systems(Last, advance_clock.run_if(in_state(AppState::InGame)));
+}
+
+/// 数据文件与录制时不同只提示，回放照常进行
+fn check_data_hashes(
+    replayer: Res<Replayer>,
+    assets: Res<ItemAssets>,
+    lists: Res<Assets<ItemList>>,
+) {
+    let current = data_hashes(&assets, &lists);
+    for recorded in &replayer.file.data {
+        match current.iter().find(|c| c.path == recorded.path) {
+            None => queue_output(CliMessage::Info(format!(
+                "⚠ 录制时的数据文件 {} 没有加载",
+                recorded.path
+            ))),
+            Some(c) if c.hash != recorded.hash => queue_output(CliMessage::Info(format!(
+                "⚠ 数据文件 {} 与录制时不同",
+                recorded.path
+            ))),
+            Some(_) => {}
+        }
+    }
+    for c in &current {
+        if !replayer.file.data.iter().any(|r| r.path == c.path) {
+            queue_output(CliMessage::Info(format!(
+                "⚠ 数据文件 {} 录制时没有",
+                c.path
+            )));
+        }
+    }
+}
+
+/// 到达录制的 tick 且上一条命令处理完后喂入下一条；全部喂完后报告结果并退出。
+/// 停在职业选择阶段说明无法以录制时的职业开始，同样退出
+#[allow(clippy::too_many_arguments)]
+fn feed_replay(
+    mut replayer: ResMut<Replayer>,
+    idle: Res<CliIdle>,
+    script: Res<CliScript>,
+    app_state: Res<State<AppState>>,
+    clock: Res<ReplayClock>,
+    commands: Res<CommandRegistry>,
+    state: StateParams,
+    mut writer: EventWriter<CliLine>,
+    mut app_exit: EventWriter<AppExit>,
+) {
+    if replayer.finished || !idle.0 {
+        return;
+    }
+    if script.io_error {
+        replayer.finished = true;
+        app_exit.write(AppExit::from_code(2));
+        return;
+    }
+    if *app_state.get() == AppState::CharacterSelect {
+        replayer.finished = true;
+        queue_output(CliMessage::Error("✘ 无法以录制时的职业开始回放".into()));
+        app_exit.write(AppExit::from_code(2));
+        return;
+    }
+
+    let index = replayer.next;
+    if let Some(command) = replayer.file.commands.get(index).cloned() {
+        if clock.0 < command.tick {
+            return;
+        }
+        replayer.next += 1;
+        if replayer.verify && replayer.divergence.is_none() {
+            let actual = state_hash(&state);
+            if actual != command.state {
+                let previous = match index {
+                    0 => "（之前没有命令，初始状态即不同）".to_string(),
+                    _ => format!("上一条命令: `{}`", replayer.file.commands[index - 1].line),
+                };
+                replayer.divergence = Some(format!(
+                    "第 {} 条命令 `{}`（tick {}）执行前状态 {actual}，录制时为 {}\n{previous}",
+                    index + 1,
+                    command.line,
+                    command.tick,
+                    command.state
+                ));
+            }
+        }
+        match commands.parse(&command.line) {
+            // 退出命令只结束回放
+            Ok(parsed) if parsed.name == "exit" => {
+                queue_output(CliMessage::UserInput(command.line, parsed.kind));
+                let len = replayer.file.commands.len();
+                replayer.next = len;
+            }
+            _ => {
+                writer.write(CliLine(command.line));
+            }
+        }
+        return;
+    }
+
+    let end = replayer.file.final_tick.unwrap_or_else(|| {
+        replayer.file.commands.last().map_or(0, |c| c.tick) + REPLAY_TAIL_TICKS
+    });
+    if clock.0 < end {
+        return;
+    }
+    replayer.finished = true;
+
+    let count = replayer.file.commands.len();
+    let code = if !replayer.verify {
+        queue_output(CliMessage::Info(format!("回放完成：{count} 条命令")));
+        0
+    } else {
+        let actual = state_hash(&state);
+        if replayer.divergence.is_none()
+            && let Some(expected) = &replayer.file.final_state
+            && *expected != actual
+        {
+            replayer.divergence = Some(format!(
+                "最终状态 {actual}，录制时为 {expected}（所有命令执行前状态一致）"
+            ));
+        }
+        match &replayer.divergence {
+            Some(divergence) => {
+                queue_output(CliMessage::Info(format!("✘ 回放出现分歧\n{divergence}")));
+                REPLAY_DIVERGED_EXIT_CODE
+            }
+            None => {
+                queue_output(CliMessage::Info(format!(
+                    "✔ 回放一致：{count} 条命令，最终状态 {actual}"
+                )));
+                0
+            }
+        }
+    };
+    app_exit.write(match NonZero::new(code) {
+        Some(code) => AppExit::Error(code),
+        None => AppExit::Success,
+    });
+}
@@ -0,0 +1,50 @@
+use super::events::*;
+use crate::interface::command::{
+    ArgKind, ArgSpec, CliCommand, CommandArgs, CommandSpec, CommandType,
+};
+
+impl CliCommand for GiveItemEvent {
+    fn spec() -> CommandSpec {
+        CommandSpec::new("give", CommandType::Game, "给予物品")
+            .target()
+            .arg(ArgSpec::required("id", ArgKind::Word, "物品 id"))
+            .arg(ArgSpec::optional("count", ArgKind::UInt, "数量").default("1"))
+    }
+
+    fn from_args(args: &CommandArgs) -> Self {
+        Self {
+            target: args.target(),
+            id: args.get("id"),
+            count: args.get("count"),
+        }
+    }
+}
+
+impl CliCommand for ListInventoryEvent {
+    fn spec() -> CommandSpec {
+        CommandSpec::new("inventory", CommandType::System, "查看物品栏")
+            .alias("inv")
+            .target()
+    }
+
+    fn from_args(args: &CommandArgs) -> Self {
+        Self {
+            target: args.target(),
+        }
+    }
+}
+
+impl CliCommand for UseItemEvent {
+    fn spec() -> CommandSpec {
+        CommandSpec::new("use", CommandType::Game, "使用物品")
+            .target()
+            .arg(ArgSpec::required("index", ArgKind::UInt, "背包索引"))
+    }
+
+    fn from_args(args: &CommandArgs) -> Self {
+        Self {
+            target: args.target(),
+            index: args.get("index"),
+        }
+    }
+}
@@ -1,16 +1,50 @@
 use bevy::prelude::*;
-use crate::data::schema::ItemEntry;
+use crate::data::registry::ItemId;
 
-/// 玩家背包（挂在 Resource）
-#[derive(Resource, Default)]
+/// 背包（挂在角色 / NPC / 箱子等任意实体上）
+#[derive(Component)]
 pub struct Backpack {
     pub slots: Vec<ItemStack>,   // 固定容量，空位用 count=0 占位
     pub capacity: usize,
 }
 
-/// 运行时物品实例
+impl Backpack {
+    /// 默认容量
+    pub const DEFAULT_CAPACITY: usize = 30;
+    /// 容量上限（读档时超出视为损坏，避免按任意容量分配格子）
+    pub const MAX_CAPACITY: usize = 1000;
+
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            slots: vec![ItemStack::empty(); capacity],
+            capacity,
+        }
+    }
+}
+
+impl Default for Backpack {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_CAPACITY)
+    }
+}
+
+/// 运行时物品实例：只存注册表句柄，定义统一从 `ItemRegistry` 读取
 #[derive(Clone)]
 pub struct ItemStack {
-    pub proto: ItemEntry,  // 直接复制静态表条目即可
+    pub item: ItemId,
     pub count: u32,
 }
+
+impl ItemStack {
+    pub fn new(item: ItemId, count: u32) -> Self {
+        Self { item, count }
+    }
+
+    /// 空槽位
+    pub fn empty() -> Self {
+        Self {
+            item: ItemId::default(),
+            count: 0,
+        }
+    }
+}
@@ -1,15 +1,20 @@
+use crate::character::target::Target;
 use bevy::prelude::*;
 
 #[derive(Event)]
 pub struct GiveItemEvent {
+    pub target: Target, // 目标实体
     pub id: String,
     pub count: u32,
 }
 
 #[derive(Event)]
-pub struct ListInventoryEvent; // 让 CLI 请求打印背包
+pub struct ListInventoryEvent {
+    pub target: Target, // 让 CLI 请求打印谁的背包
+}
 
 #[derive(Event)]
 pub struct UseItemEvent {
-    pub index: usize, // 背包索引
+    pub target: Target, // 使用者
+    pub index: usize,   // 背包索引
 }
@@ -1,32 +1,26 @@
+pub mod commands;
 pub mod components;
 pub mod events;
 mod systems;
 
 use crate::core::states::AppState;
+use crate::interface::command::CliAppExt;
 use bevy::prelude::*;
-use components::*;
 use events::*;
 use systems::*;
 
 pub struct InventoryPlugin;
 impl Plugin for InventoryPlugin {
     fn build(&self, app: &mut App) {
-        app.insert_resource(Backpack {
-            slots: vec![
-                ItemStack {
-                    proto: Default::default(),
-                    count: 0
-                };
-                30
-            ],
-            capacity: 30,
-        })
-        .add_event::<GiveItemEvent>()
-        .add_event::<ListInventoryEvent>()
-        .add_event::<UseItemEvent>()
+        app
+        // 事件 + CLI 命令：give / inventory / use
+        .register_cli_command::<GiveItemEvent>()
+        .register_cli_command::<ListInventoryEvent>()
+        .register_cli_command::<UseItemEvent>()
         .add_systems(
             Update,
-            (give_item, print_inventory, use_item).run_if(in_state(AppState::InGame)),
+            (give_item, print_inventory, use_item)
+                .run_if(in_state(AppState::InGame)),
         );
     }
 }
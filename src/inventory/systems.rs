@@ -1,144 +1,202 @@
 use super::{components::*, events::*};
-use crate::character::events::{GainExp, Heal};
+use crate::character::events::Heal;
+use crate::character::target::{Target, Targets};
 use crate::core::events::LogEvent;
-use crate::data::{ItemAssets, schema::ItemList};
+use crate::data::{registry::ItemRegistry, schema::ItemKind};
+use crate::status::events::ApplyEffect;
 use bevy::prelude::*;
 
-/// 处理"give"——往背包里塞 ItemStack
+/// 处理"give"——往目标实体的背包里塞 ItemStack；失败原因走 LogEvent，进入批处理 transcript
 pub fn give_item(
     mut ev_give: EventReader<GiveItemEvent>,
-    mut backpack: ResMut<Backpack>,
-    item_assets: Res<ItemAssets>,
-    lists: Res<Assets<ItemList>>,
+    mut backpacks: Query<&mut Backpack>,
+    targets: Targets,
+    registry: Res<ItemRegistry>,
+    mut log_event: EventWriter<LogEvent>,
 ) {
-    let list = item_assets
-        .handle
-        .as_ref()
-        .and_then(|h| lists.get(h))
-        .expect("items must be loaded");
-
     for ev in ev_give.read() {
-        if let Some(proto) = list
-            .items
-            .iter()
-            .find(|e| e.id.eq_ignore_ascii_case(&ev.id))
-        {
-            // 查找是否已有同 ID 堆叠
-            if let Some(stack) = backpack
-                .slots
-                .iter_mut()
-                .find(|s| s.count > 0 && s.proto.id == proto.id)
-            {
-                stack.count += ev.count;
-            } else {
-                // 找空位
-                if let Some(slot) = backpack.slots.iter_mut().find(|s| s.count == 0) {
-                    *slot = ItemStack {
-                        proto: proto.clone(),
-                        count: ev.count,
+        if ev.count == 0 {
+            log_event.write(LogEvent("数量必须大于 0".to_string()));
+            continue;
+        }
+
+        let resolved = targets.resolve(&ev.target);
+        if resolved.is_empty() {
+            log_event.write(LogEvent(format!("未找到目标 {}", ev.target)));
+        }
+
+        for entity in resolved {
+            let Ok(mut backpack) = backpacks.get_mut(entity) else {
+                log_event.write(LogEvent("目标没有背包".to_string()));
+                continue;
+            };
+
+            // id / uuid / 名称均可
+            if let Some(item) = registry.lookup(&ev.id) {
+                let proto = registry.get(item);
+                // 查找是否已有同 ID 堆叠
+                if let Some(stack) = backpack
+                    .slots
+                    .iter_mut()
+                    .find(|s| s.count > 0 && s.item == item)
+                {
+                    let Some(total) = stack.count.checked_add(ev.count) else {
+                        log_event.write(LogEvent(format!(
+                            "{} 数量超出上限 {}，无法获得",
+                            proto.name,
+                            u32::MAX
+                        )));
+                        continue;
                     };
+                    stack.count = total;
                 } else {
-                    warn!("背包已满，无法获得 {}", proto.name);
+                    // 找空位
+                    if let Some(slot) = backpack.slots.iter_mut().find(|s| s.count == 0) {
+                        *slot = ItemStack::new(item, ev.count);
+                    } else {
+                        log_event.write(LogEvent(format!("背包已满，无法获得 {}", proto.name)));
+                        continue;
+                    }
                 }
+                info!("获得 {} ×{}", proto.name, ev.count);
+            } else {
+                let similar: Vec<&str> = registry
+                    .search(&ev.id)
+                    .into_iter()
+                    .take(3)
+                    .map(|item| registry.get(item).id.as_str())
+                    .collect();
+                let message = if similar.is_empty() {
+                    format!("不存在物品 ID {}", ev.id)
+                } else {
+                    format!("不存在物品 ID {}，是否想要: {}", ev.id, similar.join(", "))
+                };
+                log_event.write(LogEvent(message));
             }
-            info!("获得 {} ×{}", proto.name, ev.count);
-        } else {
-            warn!("不存在物品 ID {}", ev.id);
         }
     }
 }
 
-/// 打印背包内容
-pub fn print_inventory(mut ev_list: EventReader<ListInventoryEvent>, backpack: Res<Backpack>) {
-    if ev_list.is_empty() {
-        return;
-    }
-    ev_list.clear();
-
-    let mut empty = true;
-    for (idx, stack) in backpack.slots.iter().enumerate() {
-        if stack.count > 0 {
-            empty = false;
-            println!(
-                "[{idx}] {} ×{} (id={})",
-                stack.proto.name, stack.count, stack.proto.id
-            );
+/// 打印背包内容（走 LogEvent，避免被 UI 刷新清掉、也能进入批处理 transcript）
+pub fn print_inventory(
+    mut ev_list: EventReader<ListInventoryEvent>,
+    backpacks: Query<&Backpack>,
+    targets: Targets,
+    registry: Res<ItemRegistry>,
+    mut log_event: EventWriter<LogEvent>,
+) {
+    for ev in ev_list.read() {
+        let resolved = targets.resolve(&ev.target);
+        if resolved.is_empty() {
+            log_event.write(LogEvent(format!("未找到目标 {}", ev.target)));
         }
-    }
 
-    if empty {
-        println!("  (empty)");
+        for entity in resolved {
+            let Ok(backpack) = backpacks.get(entity) else {
+                log_event.write(LogEvent("目标没有背包".to_string()));
+                continue;
+            };
+
+            let mut empty = true;
+            for (idx, stack) in backpack.slots.iter().enumerate() {
+                if stack.count > 0 {
+                    empty = false;
+                    let item = registry.get(stack.item);
+                    log_event.write(LogEvent(format!(
+                        "[{idx}] {} ×{} (id={})",
+                        item.name, stack.count, item.id
+                    )));
+                }
+            }
+
+            if empty {
+                log_event.write(LogEvent("  (empty)".into()));
+            }
+        }
     }
 }
 
-/// 使用物品
+/// 使用物品（效果作用于使用者自身）
 pub fn use_item(
     mut ev_use: EventReader<UseItemEvent>,
-    mut backpack: ResMut<Backpack>,
+    mut backpacks: Query<&mut Backpack>,
     mut log_event: EventWriter<LogEvent>,
     mut heal_event: EventWriter<Heal>,
-    mut _gain_exp_event: EventWriter<GainExp>,
-    player_query: Query<Entity, With<crate::character::components::Player>>,
+    mut effect_event: EventWriter<ApplyEffect>,
+    targets: Targets,
+    registry: Res<ItemRegistry>,
 ) {
     for ev in ev_use.read() {
-        if ev.index >= backpack.slots.len() {
-            log_event.write(LogEvent("背包索引超出范围".to_string()));
-            continue;
+        let resolved = targets.resolve(&ev.target);
+        if resolved.is_empty() {
+            log_event.write(LogEvent(format!("未找到目标 {}", ev.target)));
         }
 
-        if let Some(stack) = backpack.slots.get_mut(ev.index) {
-            if stack.count == 0 {
-                log_event.write(LogEvent("该背包格为空".to_string()));
+        for user in resolved {
+            let Ok(mut backpack) = backpacks.get_mut(user) else {
+                log_event.write(LogEvent("目标没有背包".to_string()));
+                continue;
+            };
+
+            if ev.index >= backpack.slots.len() {
+                log_event.write(LogEvent("背包索引超出范围".to_string()));
                 continue;
             }
 
-            let item = &stack.proto;
-            let item_name = item.name.clone();
+            if let Some(stack) = backpack.slots.get_mut(ev.index) {
+                if stack.count == 0 {
+                    log_event.write(LogEvent("该背包格为空".to_string()));
+                    continue;
+                }
 
-            // 根据物品类型执行不同的使用效果
-            match item.item_type.as_str() {
-                "potion" => {
-                    if item.heal > 0 {
-                        log_event.write(LogEvent(format!(
-                            "使用 {}，恢复 {} 点生命值",
-                            item_name, item.heal
-                        )));
-                        if let Ok(player_entity) = player_query.single() {
+                let item = registry.get(stack.item);
+                let item_name = item.name.clone();
+
+                // 根据物品类型执行不同的使用效果
+                match &item.kind {
+                    ItemKind::Potion { heal } => {
+                        if *heal > 0 {
+                            // 直接写游戏日志（不经 LogEvent 转发），保证排在治疗结果之前
+                            crate::interface::debug_cli::queue_game_log(format!(
+                                "使用 {}，恢复 {} 点生命值",
+                                item_name, heal
+                            ));
                             heal_event.write(Heal {
-                                entity: player_entity,
-                                amount: item.heal,
+                                target: Target::Entity(user),
+                                amount: *heal,
                             });
+                        } else {
+                            log_event
+                                .write(LogEvent(format!("使用 {}，但没有任何效果", item_name)));
                         }
-                    } else {
-                        log_event.write(LogEvent(format!("使用 {}，但没有任何效果", item_name)));
+                    }
+                    ItemKind::Scroll { effect } => {
+                        crate::interface::debug_cli::queue_game_log(format!("使用 {item_name}"));
+                        effect_event.write(ApplyEffect {
+                            entity: user,
+                            effect: effect.clone(),
+                        });
+                    }
+                    ItemKind::Key { door_id } => {
+                        log_event.write(LogEvent(format!(
+                            "使用 {}，但这里没有门 {} 可以开启",
+                            item_name, door_id
+                        )));
+                        // TODO: 实现门锁系统
+                        continue; // 钥匙不消耗
+                    }
+                    ItemKind::Weapon { .. } | ItemKind::Armor { .. } | ItemKind::Misc => {
+                        log_event.write(LogEvent(format!("{} 无法使用", item_name)));
+                        continue; // 不消耗物品
                     }
                 }
-                "scroll" => {
-                    log_event.write(LogEvent(format!("使用 {}，获得临时增益效果", item_name)));
-                    // TODO: 实现 Buff 系统
-                }
-                "key" => {
-                    log_event.write(LogEvent(format!(
-                        "使用 {}，但这里没有门可以开启",
-                        item_name
-                    )));
-                    // TODO: 实现门锁系统
-                    continue; // 钥匙不消耗
-                }
-                _ => {
-                    log_event.write(LogEvent(format!("{} 无法使用", item_name)));
-                    continue; // 不消耗物品
-                }
-            }
 
-            // 消耗物品
-            stack.count -= 1;
-            if stack.count == 0 {
-                // 清空槽位
-                *stack = ItemStack {
-                    proto: Default::default(),
-                    count: 0,
-                };
+                // 消耗物品
+                stack.count -= 1;
+                if stack.count == 0 {
+                    // 清空槽位
+                    *stack = ItemStack::empty();
+                }
             }
         }
     }
@@ -0,0 +1,116 @@
+//! 文字 RPG：各功能插件与应用组装（`main.rs` 与集成测试共用）
+
+use bevy::prelude::*;
+
+pub mod character;
+pub mod core;
+pub mod data;
+pub mod equipment;
+pub mod interface;
+pub mod inventory;
+pub mod save;
+pub mod status;
+
+use crate::character::CharacterPlugin;
+use crate::core::CorePlugin;
+use crate::core::args::LaunchArgs;
+use crate::core::states;
+use crate::data::DataPlugin;
+use crate::equipment::EquipmentPlugin;
+use crate::interface::debug_cli::DebugCliPlugin;
+use crate::inventory::InventoryPlugin;
+use crate::save::SavePlugin;
+use crate::status::StatusPlugin;
+
+/// 组装完整应用：平台插件 + 游戏插件；`--validate-data` 只需要数据加载
+pub fn build_app(args: LaunchArgs) -> App {
+    let mut app = App::new();
+    add_platform_plugins(&mut app, &args);
+    let validate_data = args.validate_data;
+    app.insert_resource(args);
+    if validate_data {
+        add_data_plugins(&mut app);
+    } else {
+        add_game_plugins(&mut app);
+    }
+    app
+}
+
+/// 平台插件：默认走 DefaultPlugins（隐藏窗口），headless 时只保留逻辑所需的最小集合
+fn add_platform_plugins(app: &mut App, args: &LaunchArgs) {
+    if args.headless {
+        use bevy::app::ScheduleRunnerPlugin;
+        use bevy::log::LogPlugin;
+        use bevy::state::app::StatesPlugin;
+        use std::time::Duration;
+
+        app.add_plugins((
+            // 固定 60 Hz 推进，避免空转占满 CPU
+            MinimalPlugins.set(ScheduleRunnerPlugin::run_loop(Duration::from_secs_f64(
+                1.0 / 60.0,
+            ))),
+            asset_plugin(),
+            StatesPlugin,
+            LogPlugin::default(),
+        ));
+    } else {
+        #[cfg(feature = "window")]
+        app.add_plugins(
+            DefaultPlugins
+                .set(WindowPlugin {
+                    primary_window: Some(Window {
+                        visible: false,
+                        ..default()
+                    }), // visible窗口，实现“无 UI”
+                    ..default()
+                })
+                .set(asset_plugin()),
+        );
+    }
+}
+
+/// 资产插件；启用 `file_watcher` feature 时监视数据文件，保存即触发热重载
+fn asset_plugin() -> AssetPlugin {
+    AssetPlugin {
+        watch_for_changes_override: Some(cfg!(feature = "file_watcher")),
+        ..default()
+    }
+}
+
+/// 游戏插件与全局系统；调用前需已插入 `LaunchArgs`（DebugCliPlugin 据此选择交互 / 批处理）
+pub fn add_game_plugins(app: &mut App) {
+    app
+        // 核心插件
+        .add_plugins(CorePlugin)
+        // 功能插件
+        .add_plugins(CharacterPlugin)
+        .add_plugins(DataPlugin)
+        .add_plugins(InventoryPlugin)
+        .add_plugins(EquipmentPlugin)
+        .add_plugins(SavePlugin)
+        .add_plugins(StatusPlugin)
+        // 交互插件
+        .add_plugins(DebugCliPlugin)
+        // 全局系统
+        .add_systems(Update, forward_log_event) // 简单打印
+        .add_systems(Startup, |mut next: ResMut<NextState<states::AppState>>| {
+            next.set(states::AppState::Loading);
+        });
+}
+
+/// 只加载与校验数据：不生成玩家、不启动 CLI
+pub fn add_data_plugins(app: &mut App) {
+    app.init_state::<states::AppState>()
+        .add_plugins(DataPlugin)
+        .add_systems(Startup, |mut next: ResMut<NextState<states::AppState>>| {
+            next.set(states::AppState::Loading);
+        });
+}
+
+fn forward_log_event(mut reader: EventReader<core::events::LogEvent>) {
+    use crate::interface::debug_cli::{queue_log_message};
+    
+    for e in reader.read() {
+        queue_log_message(e.0.clone());
+    }
+}
@@ -0,0 +1,163 @@
+//! 自动存档：每 N 条游戏命令、升级、离开 `InGame` 与退出时写入 `autosave` 槽
+//!
+//! 写入前把旧的自动存档轮换为 `autosave-1`（最新）… `autosave-<N>`，均可用
+//! `load autosave-1` 读取。进入游戏时在存档目录放一个 `.session` 标记，正常退出时
+//! 删除；启动时标记还在说明上次没有正常退出，提示玩家读取自动存档
+
+use bevy::ecs::event::EventCursor;
+use bevy::ecs::system::SystemState;
+use bevy::prelude::*;
+use std::path::{Path, PathBuf};
+
+use super::systems::{ActorQuery, capture_save, write_json};
+use crate::character::events::{LevelUp, RecalculateStats};
+use crate::core::{events::LogEvent, resources::GameConfig, rng::GameRng, states::AppState};
+use crate::data::registry::ItemRegistry;
+use crate::interface::command::{CommandRegistry, CommandType};
+use crate::interface::debug_cli::CliLine;
+
+/// 自动存档槽名
+pub const AUTOSAVE_SLOT: &str = "autosave";
+
+/// 会话标记文件名（位于存档目录）
+const SESSION_MARKER: &str = ".session";
+
+/// 自动存档进度
+#[derive(Resource, Debug, Default)]
+pub struct Autosave {
+    /// 上次自动存档后执行的游戏命令数
+    pub commands: u32,
+    /// 已触发、等本条命令的事件链处理完再写
+    pub pending: bool,
+}
+
+fn marker_path(config: &GameConfig) -> PathBuf {
+    config.save_dir.join(SESSION_MARKER)
+}
+
+fn autosave_path(dir: &Path, generation: usize) -> PathBuf {
+    match generation {
+        0 => dir.join(format!("{AUTOSAVE_SLOT}.json")),
+        n => dir.join(format!("{AUTOSAVE_SLOT}-{n}.json")),
+    }
+}
+
+/// 进入游戏：检查上次是否正常退出，然后放置本次的会话标记
+pub fn begin_session(config: Res<GameConfig>, mut log_event: EventWriter<LogEvent>) {
+    let marker = marker_path(&config);
+    if marker.exists() && autosave_path(&config.save_dir, 0).exists() {
+        log_event.write(LogEvent(format!(
+            "⚠ 上次游戏没有正常退出，输入 `load {AUTOSAVE_SLOT}` 可恢复最近的自动存档"
+        )));
+    }
+    let written = std::fs::create_dir_all(&config.save_dir)
+        .and_then(|_| std::fs::write(&marker, std::process::id().to_string()));
+    if let Err(err) = written {
+        warn!("无法写入会话标记 {}: {err}", marker.display());
+    }
+}
+
+/// 统计成功解析的游戏命令并监听升级，满足条件时标记待存档
+pub fn track_autosave_triggers(
+    mut lines: EventReader<CliLine>,
+    mut level_ups: EventReader<LevelUp>,
+    commands: Res<CommandRegistry>,
+    config: Res<GameConfig>,
+    mut autosave: ResMut<Autosave>,
+) {
+    for CliLine(line) in lines.read() {
+        if commands
+            .parse(line)
+            .is_ok_and(|command| command.kind == CommandType::Game)
+        {
+            autosave.commands += 1;
+        }
+    }
+    if config.autosave_every > 0 && autosave.commands >= config.autosave_every {
+        autosave.pending = true;
+    }
+    if level_ups.read().count() > 0 {
+        autosave.pending = true;
+    }
+}
+
+/// 生成存档内容所需的参数
+type SaveParams = (
+    ActorQuery<'static, 'static>,
+    Res<'static, ItemRegistry>,
+    Res<'static, GameRng>,
+    Res<'static, GameConfig>,
+);
+
+/// 待存档且本帧没有未处理的命令事件与属性重算时写入，保证存下的是命令执行后的状态
+pub fn run_pending_autosave(world: &mut World, params: &mut SystemState<SaveParams>) {
+    if !world.resource::<Autosave>().pending
+        || !world.resource::<Events<CliLine>>().is_empty()
+        || !world.resource::<Events<RecalculateStats>>().is_empty()
+        || world.resource::<CommandRegistry>().has_pending_events(world)
+    {
+        return;
+    }
+    write_autosave(world, params);
+}
+
+/// 离开 `InGame` 前存一次
+pub fn autosave_on_exit_state(world: &mut World, params: &mut SystemState<SaveParams>) {
+    write_autosave(world, params);
+}
+
+/// 退出：游戏中先自动存档，然后删除会话标记
+pub fn autosave_on_app_exit(
+    world: &mut World,
+    params: &mut SystemState<SaveParams>,
+    mut cursor: Local<EventCursor<AppExit>>,
+) {
+    if cursor.read(world.resource::<Events<AppExit>>()).count() == 0 {
+        return;
+    }
+    if *world.resource::<State<AppState>>().get() == AppState::InGame {
+        write_autosave(world, params);
+    }
+    let marker = marker_path(world.resource::<GameConfig>());
+    if let Err(err) = std::fs::remove_file(&marker)
+        && err.kind() != std::io::ErrorKind::NotFound
+    {
+        warn!("无法删除会话标记 {}: {err}", marker.display());
+    }
+}
+
+/// 轮换旧存档后写入 `autosave`；没有玩家时（存档无法读回）跳过
+fn write_autosave(world: &mut World, params: &mut SystemState<SaveParams>) {
+    *world.resource_mut::<Autosave>() = Autosave::default();
+    let (actors, registry, rng, config) = params.get(world);
+    let save = capture_save(&actors, &registry, &rng);
+    if !save.actors.iter().any(|actor| actor.player) {
+        return;
+    }
+
+    let dir = &config.save_dir;
+    let written = rotate_backups(dir, config.autosave_backups)
+        .and_then(|_| write_json(&autosave_path(dir, 0), &save));
+    if let Err(err) = written {
+        world.send_event(LogEvent(format!("✘ 自动存档失败: {err}")));
+    }
+}
+
+/// `autosave-<n-1>` → `autosave-<n>` … `autosave` → `autosave-1`，最旧的一份被丢弃
+fn rotate_backups(dir: &Path, backups: usize) -> std::io::Result<()> {
+    if backups == 0 {
+        return Ok(());
+    }
+    for generation in (1..backups).rev() {
+        let from = autosave_path(dir, generation);
+        if from.exists() {
+            std::fs::rename(from, autosave_path(dir, generation + 1))?;
+        }
+    }
+    // 复制而非改名：新存档写好之前 `autosave` 始终可读
+    let latest = autosave_path(dir, 0);
+    if latest.exists() {
+        std::fs::copy(latest, autosave_path(dir, 1))?;
+    }
+    Ok(())
+}
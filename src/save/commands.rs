@@ -0,0 +1,30 @@
+use super::events::*;
+use crate::interface::command::{
+    ArgKind, ArgSpec, CliCommand, CommandArgs, CommandSpec, CommandType,
+};
+
+impl CliCommand for SaveGame {
+    fn spec() -> CommandSpec {
+        CommandSpec::new("save", CommandType::System, "保存游戏")
+            .arg(ArgSpec::required("slot", ArgKind::Word, "存档名（字母、数字、- 和 _）"))
+    }
+
+    fn from_args(args: &CommandArgs) -> Self {
+        Self {
+            slot: args.get("slot"),
+        }
+    }
+}
+
+impl CliCommand for LoadGame {
+    fn spec() -> CommandSpec {
+        CommandSpec::new("load", CommandType::System, "读取存档")
+            .arg(ArgSpec::required("slot", ArgKind::Word, "存档名"))
+    }
+
+    fn from_args(args: &CommandArgs) -> Self {
+        Self {
+            slot: args.get("slot"),
+        }
+    }
+}
@@ -0,0 +1,13 @@
+use bevy::prelude::*;
+
+/// 保存当前游戏到存档槽（CLI `save <slot>`）
+#[derive(Event)]
+pub struct SaveGame {
+    pub slot: String,
+}
+
+/// 从存档槽恢复游戏（CLI `load <slot>`）
+#[derive(Event)]
+pub struct LoadGame {
+    pub slot: String,
+}
@@ -0,0 +1,178 @@
+//! 存档迁移：旧版本存档按 [`MIGRATIONS`] 逐级升级到 [`SAVE_VERSION`]
+//!
+//! 迁移在反序列化之前对 JSON 值操作，所以旧格式不需要保留对应的 Rust 结构。
+//! 修改存档格式时：`SAVE_VERSION` + 1，在 `MIGRATIONS` 末尾追加一步，
+//! 并在 `tests/fixtures/saves/` 放一份新版本的样例存档
+
+use serde_json::{Map, Value};
+
+use super::schema::SAVE_VERSION;
+
+/// 一步迁移：把 `from` 版本的存档改写为 `from + 1` 版本
+pub struct Migration {
+    pub from: u32,
+    pub describe: &'static str,
+    pub apply: fn(&mut Value) -> Result<(), String>,
+}
+
+/// 按版本顺序排列，`MIGRATIONS[i].from == i + 1`
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        from: 1,
+        describe: "stats/base_stats 拆为 level/exp/hp + base/current 属性",
+        apply: v1_to_v2,
+    },
+    Migration {
+        from: 2,
+        describe: "新增随机数状态 rng（旧存档没有，读档时沿用当前随机数）",
+        apply: v2_to_v3,
+    },
+    Migration {
+        from: 3,
+        describe: "角色新增状态效果 effects（旧存档没有生效中的效果）",
+        apply: v3_to_v4,
+    },
+    Migration {
+        from: 4,
+        describe: "角色新增技能点 skill_points（旧存档为 0）",
+        apply: v4_to_v5,
+    },
+    Migration {
+        from: 5,
+        describe: "玩家新增职业 class（旧存档没有职业，不限制装备）",
+        apply: v5_to_v6,
+    },
+    Migration {
+        from: 6,
+        describe: "移除派生属性 current（读档时按当前数据重算）",
+        apply: v6_to_v7,
+    },
+    Migration {
+        from: 7,
+        describe: "角色新增增益 / 光环修正 modifiers（旧存档没有）",
+        apply: v7_to_v8,
+    },
+];
+
+/// 迁移结果：原始版本与实际执行的步骤
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Migrated {
+    pub from: u32,
+    pub steps: Vec<&'static str>,
+}
+
+/// 读取存档的版本号
+pub fn version_of(save: &Value) -> Result<u32, String> {
+    let version = save
+        .get("version")
+        .ok_or("缺少 version 字段")?
+        .as_u64()
+        .ok_or("version 应为正整数")?;
+    u32::try_from(version).map_err(|_| format!("存档版本 {version} 不受支持"))
+}
+
+/// 原地升级到当前版本；已是当前版本时不做任何事
+pub fn migrate(save: &mut Value) -> Result<Migrated, String> {
+    let from = version_of(save)?;
+    if from > SAVE_VERSION {
+        return Err(format!("存档版本 {from} 比游戏新（当前版本 {SAVE_VERSION}）"));
+    }
+    if from == 0 {
+        return Err("存档版本 0 不受支持".into());
+    }
+
+    let mut steps = Vec::new();
+    for migration in MIGRATIONS.iter().filter(|m| m.from >= from) {
+        (migration.apply)(save)
+            .map_err(|err| format!("v{} → v{} 失败: {err}", migration.from, migration.from + 1))?;
+        save["version"] = Value::from(migration.from + 1);
+        steps.push(migration.describe);
+    }
+    Ok(Migrated { from, steps })
+}
+
+/// v1 直接序列化 `Stats` / `BaseStats` 组件，等级与经验在两处各存一份；
+/// v2 只存一份 level/exp/hp，属性分为 base（不含装备）与 current
+fn v1_to_v2(save: &mut Value) -> Result<(), String> {
+    let actors = save
+        .get_mut("actors")
+        .and_then(Value::as_array_mut)
+        .ok_or("actors 缺失")?;
+    for (idx, actor) in actors.iter_mut().enumerate() {
+        let actor = actor
+            .as_object_mut()
+            .ok_or_else(|| format!("actors[{idx}] 应为对象"))?;
+        let stats = take_object(actor, "stats", idx)?;
+        let base_stats = take_object(actor, "base_stats", idx)?;
+
+        for (field, key) in [("level", "lv"), ("exp", "exp"), ("hp", "hp")] {
+            actor.insert(field.into(), field_of(&stats, key, "stats", idx)?);
+        }
+        actor.insert("base".into(), attributes(&base_stats, "base_stats", idx)?);
+        actor.insert("current".into(), attributes(&stats, "stats", idx)?);
+    }
+    Ok(())
+}
+
+/// v3 只新增了可选的 `rng`，内容不变
+fn v2_to_v3(_save: &mut Value) -> Result<(), String> {
+    Ok(())
+}
+
+/// v4 只新增了可选的 `effects`，内容不变
+fn v3_to_v4(_save: &mut Value) -> Result<(), String> {
+    Ok(())
+}
+
+/// v5 只新增了可选的 `skill_points`，内容不变
+fn v4_to_v5(_save: &mut Value) -> Result<(), String> {
+    Ok(())
+}
+
+/// v6 只新增了可选的 `class`，内容不变
+fn v5_to_v6(_save: &mut Value) -> Result<(), String> {
+    Ok(())
+}
+
+/// v7 不再保存含加成的 `current`，读档时由基础属性与修正重算
+fn v6_to_v7(save: &mut Value) -> Result<(), String> {
+    let actors = save
+        .get_mut("actors")
+        .and_then(Value::as_array_mut)
+        .ok_or("actors 缺失")?;
+    for (idx, actor) in actors.iter_mut().enumerate() {
+        actor
+            .as_object_mut()
+            .ok_or_else(|| format!("actors[{idx}] 应为对象"))?
+            .remove("current");
+    }
+    Ok(())
+}
+
+/// v8 只新增了可选的 `modifiers`，内容不变
+fn v7_to_v8(_save: &mut Value) -> Result<(), String> {
+    Ok(())
+}
+
+fn take_object(actor: &mut Map<String, Value>, key: &str, idx: usize) -> Result<Value, String> {
+    match actor.remove(key) {
+        Some(value @ Value::Object(_)) => Ok(value),
+        Some(_) => Err(format!("actors[{idx}].{key} 应为对象")),
+        None => Err(format!("actors[{idx}].{key} 缺失")),
+    }
+}
+
+fn field_of(object: &Value, key: &str, owner: &str, idx: usize) -> Result<Value, String> {
+    object
+        .get(key)
+        .cloned()
+        .ok_or_else(|| format!("actors[{idx}].{owner}.{key} 缺失"))
+}
+
+fn attributes(object: &Value, owner: &str, idx: usize) -> Result<Value, String> {
+    let mut out = Map::new();
+    for key in ["max_hp", "atk", "def", "rng"] {
+        out.insert(key.into(), field_of(object, key, owner, idx)?);
+    }
+    Ok(Value::Object(out))
+}
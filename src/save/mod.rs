@@ -0,0 +1,46 @@
+pub mod autosave;
+pub mod commands;
+pub mod events;
+pub mod migrate;
+pub mod schema;
+pub mod systems;
+pub mod tool;
+
+use crate::character::systems::recalculate_stats;
+use crate::core::states::AppState;
+use crate::interface::command::CliAppExt;
+use bevy::prelude::*;
+use autosave::*;
+use events::*;
+use systems::*;
+
+pub struct SavePlugin;
+
+impl Plugin for SavePlugin {
+    fn build(&self, app: &mut App) {
+        app
+            // 事件 + CLI 命令：save / load
+            .register_cli_command::<SaveGame>()
+            .register_cli_command::<LoadGame>()
+            .add_systems(
+                Update,
+                (save_game, load_game, track_autosave_triggers)
+                    .chain()
+                    // 读档生成的实体要在重算属性之前落地
+                    .before(recalculate_stats)
+                    .run_if(in_state(AppState::InGame)),
+            )
+            // 自动存档：命令计数 / 升级触发，离开游戏与退出时补存
+            .init_resource::<Autosave>()
+            .add_systems(OnEnter(AppState::InGame), begin_session)
+            .add_systems(OnExit(AppState::InGame), autosave_on_exit_state)
+            .add_systems(
+                Last,
+                (
+                    run_pending_autosave.run_if(in_state(AppState::InGame)),
+                    autosave_on_app_exit,
+                )
+                    .chain(),
+            );
+    }
+}
@@ -0,0 +1,434 @@
+//! 存档文件结构：物品只按 id 引用，读档时重新从 `ItemRegistry` 解析，
+//! 数据更新后旧存档里的物品自动使用新定义。只保存基础属性，含装备等加成的
+//! 属性读档时按当前数据重算。属性用存档自己的结构保存，
+//! 组件改动不直接影响文件格式；格式变化时升级 [`SAVE_VERSION`] 并在
+//! [`super::migrate`] 中追加迁移
+//!
+//! ```json
+//! {
+//!   "version": 8,
+//!   "actors": [
+//!     {
+//!       "player": true,
+//!       "class": "warrior",
+//!       "faction": "player",
+//!       "level": 3,
+//!       "exp": 50,
+//!       "hp": 15,
+//!       "base": { "max_hp": 20, "atk": 2, "def": 1, "rng": 1 },
+//!       "backpack": { "capacity": 30, "slots": [{ "index": 0, "item": "potion_heal_small", "count": 2 }] },
+//!       "equipment": { "weapon": "sword_iron" },
+//!       "skill_points": 1,
+//!       "effects": [{ "effect": { "id": "regen", "name": "回春", "duration": { "Turns": 3 }, "tick_heal": 2 }, "stacks": 1, "remaining": 2, "elapsed": 1 }],
+//!       "modifiers": [{ "source": "buff:rage", "stat": "Atk", "op": { "Flat": 5 } }]
+//!     }
+//!   ],
+//!   "rng": { "seed": 123, "combat": 64, "loot": 0, "mapgen": 0, "ai": 0 }
+//! }
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+use super::migrate::{Migrated, migrate};
+use crate::character::components::{BaseStats, Faction, Stats};
+use crate::character::modifiers::{ModifierOp, ModifierSource, Stat, StatModifier, StatModifiers};
+use crate::core::rng::RngState;
+use crate::data::registry::ItemRegistry;
+use crate::data::schema::EffectSpec;
+use crate::equipment::components::Equipment;
+use crate::inventory::components::{Backpack, ItemStack};
+use crate::status::components::{ActiveEffect, StatusEffects};
+
+/// 当前存档格式版本
+pub const SAVE_VERSION: u32 = 8;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SaveFile {
+    pub version: u32,
+    /// 玩家在前，其余按生成顺序
+    pub actors: Vec<SavedActor>,
+    /// 随机数状态；缺省时读档保留当前随机数
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rng: Option<RngState>,
+}
+
+/// 一个角色实体
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SavedActor {
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub player: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// 职业 id；读档时职业已不存在则不限制装备、使用默认成长
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub class: Option<String>,
+    pub faction: Faction,
+    pub level: i32,
+    pub exp: i32,
+    pub hp: i32,
+    /// 不含等级、装备等加成的基础属性
+    pub base: SavedAttributes,
+    pub backpack: SavedBackpack,
+    pub equipment: SavedEquipment,
+    /// 尚未分配的技能点（升级奖励）
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub skill_points: u32,
+    /// 生效中的状态效果（带完整声明，物品数据变化不影响已有效果）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub effects: Vec<SavedEffect>,
+    /// 增益与光环修正；效果的修正由 `effects` 重新生成，不在这里保存
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub modifiers: Vec<SavedModifier>,
+}
+
+fn is_zero(value: &u32) -> bool {
+    *value == 0
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SavedAttributes {
+    pub max_hp: i32,
+    pub atk: i32,
+    pub def: i32,
+    pub rng: i32,
+}
+
+impl SavedActor {
+    /// 从组件生成；等级与经验以 `BaseStats` 为准
+    pub fn capture(
+        faction: Faction,
+        stats: &Stats,
+        base_stats: &BaseStats,
+        backpack: SavedBackpack,
+        equipment: SavedEquipment,
+    ) -> Self {
+        Self {
+            player: false,
+            name: None,
+            class: None,
+            faction,
+            level: base_stats.lv,
+            exp: base_stats.exp,
+            hp: stats.hp,
+            base: SavedAttributes {
+                max_hp: base_stats.max_hp,
+                atk: base_stats.atk,
+                def: base_stats.def,
+                rng: base_stats.rng,
+            },
+            backpack,
+            equipment,
+            skill_points: 0,
+            effects: Vec::new(),
+            modifiers: Vec::new(),
+        }
+    }
+
+    /// 读档时的初始 `Stats`：只带基础属性、等级、经验与生命值，派生属性由
+    /// `RecalculateStats` 按当前数据重算；`max_hp` 为 0，重算时生命值只截到新的上限
+    pub fn stats(&self) -> Stats {
+        Stats {
+            hp: self.hp,
+            max_hp: 0,
+            ..Stats::from(&self.base_stats())
+        }
+    }
+
+    pub fn base_stats(&self) -> BaseStats {
+        BaseStats {
+            max_hp: self.base.max_hp,
+            atk: self.base.atk,
+            def: self.base.def,
+            lv: self.level,
+            exp: self.exp,
+            rng: self.base.rng,
+        }
+    }
+}
+
+/// 一个状态效果及其进度
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SavedEffect {
+    pub effect: EffectSpec,
+    pub stacks: u32,
+    pub remaining: u32,
+    pub elapsed: u32,
+}
+
+impl SavedEffect {
+    pub fn capture(effects: &StatusEffects) -> Vec<Self> {
+        effects
+            .0
+            .iter()
+            .map(|active| Self {
+                effect: active.spec.clone(),
+                stacks: active.stacks,
+                remaining: active.remaining,
+                elapsed: active.elapsed,
+            })
+            .collect()
+    }
+
+    /// 读档后从下一回合开始计时
+    pub fn restore(saved: &[Self]) -> StatusEffects {
+        StatusEffects(
+            saved
+                .iter()
+                .map(|saved| ActiveEffect {
+                    spec: saved.effect.clone(),
+                    stacks: saved.stacks,
+                    remaining: saved.remaining,
+                    elapsed: saved.elapsed,
+                    applied_turn: 0,
+                })
+                .collect(),
+        )
+    }
+}
+
+/// 一条增益 / 光环修正；来源按标签保存（`buff:<name>` / `aura:<name>`）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SavedModifier {
+    pub source: String,
+    pub stat: Stat,
+    pub op: ModifierOp,
+}
+
+impl SavedModifier {
+    /// 只保存外部添加的来源：等级与装备读档时重新生成，效果随 `effects` 恢复
+    pub fn capture(modifiers: &StatModifiers) -> Vec<Self> {
+        modifiers
+            .0
+            .iter()
+            .filter(|modifier| {
+                matches!(modifier.source, ModifierSource::Buff(_) | ModifierSource::Aura(_))
+            })
+            .map(|modifier| Self {
+                source: modifier.source.to_string(),
+                stat: modifier.stat,
+                op: modifier.op,
+            })
+            .collect()
+    }
+
+    /// 调用前需已通过 [`SaveFile::validate`]，无法解析的来源被跳过
+    pub fn restore(saved: &[Self]) -> StatModifiers {
+        StatModifiers(
+            saved
+                .iter()
+                .filter_map(|saved| {
+                    let source = saved.source.parse().ok()?;
+                    Some(StatModifier::new(saved.stat, saved.op, source))
+                })
+                .collect(),
+        )
+    }
+}
+
+/// 背包只记录非空格子
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SavedBackpack {
+    pub capacity: usize,
+    pub slots: Vec<SavedStack>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SavedStack {
+    pub index: usize,
+    pub item: String,
+    pub count: u32,
+}
+
+/// 各装备槽的物品 id
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SavedEquipment {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub head: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weapon: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub accessory: Option<String>,
+}
+
+impl SavedBackpack {
+    pub fn capture(backpack: &Backpack, registry: &ItemRegistry) -> Self {
+        let slots = backpack
+            .slots
+            .iter()
+            .enumerate()
+            .filter(|(_, stack)| stack.count > 0)
+            .map(|(index, stack)| SavedStack {
+                index,
+                item: registry.get(stack.item).id.clone(),
+                count: stack.count,
+            })
+            .collect();
+        Self {
+            capacity: backpack.capacity,
+            slots,
+        }
+    }
+
+    /// 按 id 重新解析；调用前需已通过 [`SaveFile::validate`]
+    pub fn restore(&self, registry: &ItemRegistry) -> Backpack {
+        let mut backpack = Backpack::new(self.capacity);
+        for saved in &self.slots {
+            if let Some(item) = registry.by_id(&saved.item) {
+                backpack.slots[saved.index] = ItemStack::new(item, saved.count);
+            }
+        }
+        backpack
+    }
+}
+
+impl SavedEquipment {
+    pub fn capture(equipment: &Equipment, registry: &ItemRegistry) -> Self {
+        let id = |slot: &Option<ItemStack>| slot.as_ref().map(|s| registry.get(s.item).id.clone());
+        Self {
+            head: id(&equipment.head),
+            body: id(&equipment.body),
+            weapon: id(&equipment.weapon),
+            accessory: id(&equipment.accessory),
+        }
+    }
+
+    pub fn restore(&self, registry: &ItemRegistry) -> Equipment {
+        let stack = |id: &Option<String>| {
+            id.as_deref()
+                .and_then(|id| registry.by_id(id))
+                .map(|item| ItemStack::new(item, 1))
+        };
+        Equipment {
+            head: stack(&self.head),
+            body: stack(&self.body),
+            weapon: stack(&self.weapon),
+            accessory: stack(&self.accessory),
+        }
+    }
+
+    /// (槽位, 物品 id)
+    fn slots(&self) -> impl Iterator<Item = (&'static str, &str)> {
+        [
+            ("head", &self.head),
+            ("body", &self.body),
+            ("weapon", &self.weapon),
+            ("accessory", &self.accessory),
+        ]
+        .into_iter()
+        .filter_map(|(slot, id)| id.as_deref().map(|id| (slot, id)))
+    }
+}
+
+impl SaveFile {
+    /// 解析存档文本，旧版本先迁移到当前版本（不检查物品）
+    pub fn from_json(text: &str) -> Result<(Self, Migrated), String> {
+        let mut value: serde_json::Value =
+            serde_json::from_str(text).map_err(|err| format!("格式错误: {err}"))?;
+        let migrated = migrate(&mut value)?;
+        let save = serde_json::from_value(value).map_err(|err| format!("格式错误: {err}"))?;
+        Ok((save, migrated))
+    }
+
+    /// 读档前检查（已迁移到当前版本）：版本、恰好一个玩家、背包容量不超上限、物品 id 存在且能放进对应位置、属性取值合理。
+    /// 生命值上限取决于重算结果，这里只检查下限，超出部分在重算后截掉。返回全部问题
+    pub fn validate(&self, registry: &ItemRegistry) -> Vec<String> {
+        let mut issues = Vec::new();
+        if self.version != SAVE_VERSION {
+            issues.push(format!(
+                "存档版本 {} 不受支持（当前版本 {SAVE_VERSION}）",
+                self.version
+            ));
+            return issues;
+        }
+
+        let players = self.actors.iter().filter(|a| a.player).count();
+        if players != 1 {
+            issues.push(format!("存档应包含 1 个玩家，实际 {players} 个"));
+        }
+
+        for (idx, actor) in self.actors.iter().enumerate() {
+            let who = match (&actor.name, actor.player) {
+                (_, true) => "玩家".to_string(),
+                (Some(name), _) => name.clone(),
+                (None, _) => format!("角色 #{idx}"),
+            };
+
+            if actor.level < 1 {
+                issues.push(format!("{who}: 等级必须 ≥ 1"));
+            }
+            if actor.hp < 0 {
+                issues.push(format!("{who}: 生命值 {} 不能为负", actor.hp));
+            }
+            if actor.base.max_hp <= 0 {
+                issues.push(format!("{who}: 基础生命上限 {} 应为正数", actor.base.max_hp));
+            }
+
+            let backpack = &actor.backpack;
+            if backpack.capacity > Backpack::MAX_CAPACITY {
+                issues.push(format!(
+                    "{who} 背包: 容量 {} 超过上限 {}",
+                    backpack.capacity,
+                    Backpack::MAX_CAPACITY
+                ));
+            }
+            // 容量超限时只按上限检查格子，不按存档里的容量分配
+            let capacity = backpack.capacity.min(Backpack::MAX_CAPACITY);
+            let mut used = vec![false; capacity];
+            for saved in &backpack.slots {
+                let at = format!("{who} 背包 [{}]", saved.index);
+                if saved.index >= capacity {
+                    issues.push(format!("{at}: 超出容量 {capacity}"));
+                } else if std::mem::replace(&mut used[saved.index], true) {
+                    issues.push(format!("{at}: 格子重复"));
+                }
+                if saved.count == 0 {
+                    issues.push(format!("{at}: 数量为 0"));
+                }
+                if registry.by_id(&saved.item).is_none() {
+                    issues.push(format!("{at}: 未知物品 id {}", saved.item));
+                }
+            }
+
+            for saved in &actor.effects {
+                let at = format!("{who} 效果 {}", saved.effect.id);
+                if saved.stacks == 0 {
+                    issues.push(format!("{at}: 层数为 0"));
+                }
+                if saved.remaining == 0 || saved.remaining > saved.effect.duration.amount() {
+                    issues.push(format!(
+                        "{at}: 剩余 {} 超出范围 1..={}",
+                        saved.remaining,
+                        saved.effect.duration.amount()
+                    ));
+                }
+            }
+
+            for saved in &actor.modifiers {
+                if let Err(err) = saved.source.parse::<ModifierSource>() {
+                    issues.push(format!("{who} 修正 {}: {err}", saved.source));
+                }
+            }
+
+            for (slot, id) in actor.equipment.slots() {
+                match registry.by_id(id) {
+                    None => issues.push(format!("{who} 装备 {slot}: 未知物品 id {id}")),
+                    Some(item) if registry.get(item).kind.equip_slot() != Some(slot) => {
+                        issues.push(format!("{who} 装备 {slot}: {id} 不能装备在该槽"));
+                    }
+                    Some(_) => {}
+                }
+            }
+        }
+        issues
+    }
+}
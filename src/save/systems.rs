@@ -0,0 +1,212 @@
+use super::migrate::Migrated;
+use super::{events::*, schema::*};
+use crate::character::components::{
+    BaseStats, CharacterClass, Faction, Player, SkillPoints, Stats,
+};
+use crate::character::events::RecalculateStats;
+use crate::character::modifiers::StatModifiers;
+use crate::core::{events::LogEvent, resources::GameConfig, rng::GameRng};
+use crate::data::registry::ItemRegistry;
+use crate::equipment::components::Equipment;
+use crate::inventory::components::Backpack;
+use crate::status::components::StatusEffects;
+use bevy::prelude::*;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// 存档槽对应的文件：`<save_dir>/<slot>.json`；槽名只允许字母、数字、- 和 _
+pub fn slot_path(config: &GameConfig, slot: &str) -> Result<PathBuf, String> {
+    let valid = !slot.is_empty()
+        && slot
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !valid {
+        return Err(format!("存档名 \"{slot}\" 只能包含字母、数字、- 和 _"));
+    }
+    Ok(config.save_dir.join(format!("{slot}.json")))
+}
+
+/// 需要存档的角色：玩家在前，其余按实体 id 排序
+pub type ActorQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        Has<Player>,
+        Option<&'static Name>,
+        Option<&'static CharacterClass>,
+        &'static Faction,
+        &'static Stats,
+        &'static BaseStats,
+        &'static Backpack,
+        &'static Equipment,
+        Option<&'static SkillPoints>,
+        Option<&'static StatusEffects>,
+        Option<&'static StatModifiers>,
+    ),
+>;
+
+pub fn save_game(
+    mut ev_save: EventReader<SaveGame>,
+    actors: ActorQuery,
+    registry: Res<ItemRegistry>,
+    rng: Res<GameRng>,
+    config: Res<GameConfig>,
+    mut log_event: EventWriter<LogEvent>,
+) {
+    for ev in ev_save.read() {
+        let path = match slot_path(&config, &ev.slot) {
+            Ok(path) => path,
+            Err(err) => {
+                log_event.write(LogEvent(format!("✘ {err}")));
+                continue;
+            }
+        };
+
+        match write_json(&path, &capture_save(&actors, &registry, &rng)) {
+            Ok(()) => log_event.write(LogEvent(format!("✔ 已保存存档 {}", ev.slot))),
+            Err(err) => log_event.write(LogEvent(format!("✘ 保存存档 {} 失败: {err}", ev.slot))),
+        };
+    }
+}
+
+/// 当前所有角色的存档内容
+pub fn capture_save(actors: &ActorQuery, registry: &ItemRegistry, rng: &GameRng) -> SaveFile {
+    let mut sorted: Vec<_> = actors.iter().collect();
+    sorted.sort_by_key(|(entity, player, ..)| (!player, entity.index()));
+    SaveFile {
+        version: SAVE_VERSION,
+        actors: sorted
+            .into_iter()
+            .map(
+                |(_, player, name, class, faction, stats, base_stats, backpack, equipment, points, effects, modifiers)| SavedActor {
+                    player,
+                    name: name.map(|n| n.as_str().to_string()),
+                    class: class.map(|class| class.0.clone()),
+                    skill_points: points.map_or(0, |points| points.0),
+                    effects: effects.map(SavedEffect::capture).unwrap_or_default(),
+                    modifiers: modifiers.map(SavedModifier::capture).unwrap_or_default(),
+                    ..SavedActor::capture(
+                        *faction,
+                        stats,
+                        base_stats,
+                        SavedBackpack::capture(backpack, registry),
+                        SavedEquipment::capture(equipment, registry),
+                    )
+                },
+            )
+            .collect(),
+        rng: Some(rng.state()),
+    }
+}
+
+/// 先写同目录下的 `<file>.tmp` 并落盘，再改名覆盖：中途崩溃不会留下写了一半的文件
+pub fn write_json(path: &Path, value: &impl serde::Serialize) -> std::io::Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let json = serde_json::to_string_pretty(value).map_err(std::io::Error::other)?;
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    let tmp = PathBuf::from(tmp);
+
+    let mut file = std::fs::File::create(&tmp)?;
+    file.write_all(json.as_bytes())?;
+    file.sync_all()?;
+    drop(file);
+    std::fs::rename(&tmp, path)
+}
+
+/// 读档：校验通过后替换所有角色实体，任何问题都不改动当前世界。
+/// 派生属性不从存档读取，生成后按当前物品数据与修正重算
+#[allow(clippy::too_many_arguments)]
+pub fn load_game(
+    mut commands: Commands,
+    mut ev_load: EventReader<LoadGame>,
+    mut ev_recalculate: EventWriter<RecalculateStats>,
+    actors: Query<Entity, With<Stats>>,
+    registry: Res<ItemRegistry>,
+    mut rng: ResMut<GameRng>,
+    config: Res<GameConfig>,
+    mut log_event: EventWriter<LogEvent>,
+) {
+    for ev in ev_load.read() {
+        let save = slot_path(&config, &ev.slot).and_then(|path| read_save(&path, &registry));
+        let (save, migrated) = match save {
+            Ok(loaded) => loaded,
+            Err(err) => {
+                log_event.write(LogEvent(format!(
+                    "✘ 读取存档 {} 失败，游戏保持不变\n{err}",
+                    ev.slot
+                )));
+                continue;
+            }
+        };
+
+        for entity in &actors {
+            commands.entity(entity).despawn();
+        }
+        if let Some(state) = &save.rng {
+            *rng = GameRng::from_state(state);
+        }
+        for actor in &save.actors {
+            let effects = SavedEffect::restore(&actor.effects);
+            let mut modifiers = SavedModifier::restore(&actor.modifiers);
+            for effect in &effects.0 {
+                effect.sync_modifiers(&mut modifiers);
+            }
+            let mut entity = commands.spawn((
+                actor.faction,
+                actor.stats(),
+                actor.base_stats(),
+                actor.backpack.restore(&registry),
+                actor.equipment.restore(&registry),
+                SkillPoints(actor.skill_points),
+                modifiers,
+                effects,
+            ));
+            if actor.player {
+                entity.insert(Player);
+            }
+            if let Some(name) = &actor.name {
+                entity.insert(Name::new(name.clone()));
+            }
+            if let Some(class) = &actor.class {
+                entity.insert(CharacterClass(class.clone()));
+            }
+            ev_recalculate.write(RecalculateStats { entity: entity.id() });
+        }
+        let upgraded = if migrated.steps.is_empty() {
+            String::new()
+        } else {
+            format!("，已从 v{} 升级", migrated.from)
+        };
+        log_event.write(LogEvent(format!(
+            "✔ 已读取存档 {}（{} 个角色{upgraded}）",
+            ev.slot,
+            save.actors.len()
+        )));
+    }
+}
+
+/// 读取、迁移并校验存档文件
+pub fn read_save(
+    path: &Path,
+    registry: &ItemRegistry,
+) -> Result<(SaveFile, Migrated), String> {
+    let text = match std::fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+            return Err("存档不存在".into());
+        }
+        Err(err) => return Err(err.to_string()),
+    };
+    let (save, migrated) = SaveFile::from_json(&text)?;
+
+    let issues = save.validate(registry);
+    if issues.is_empty() {
+        Ok((save, migrated))
+    } else {
+        Err(format!("{} 个问题\n  {}", issues.len(), issues.join("\n  ")))
+    }
+}
@@ -0,0 +1,182 @@
+//! 离线存档工具：`save inspect <file>` 打印存档内容，`save migrate <file> [out]`
+//! 升级到当前版本。不启动游戏、不加载物品数据，所以不检查物品 id
+
+use std::path::{Path, PathBuf};
+
+use super::migrate::Migrated;
+use super::schema::{SAVE_VERSION, SaveFile};
+use super::systems::write_json;
+
+/// `save` 子命令
+#[derive(Debug, Clone, PartialEq)]
+pub enum SaveTool {
+    Inspect(PathBuf),
+    /// 缺省 `output` 时原地升级，旧文件备份为 `<file>.bak`
+    Migrate {
+        input: PathBuf,
+        output: Option<PathBuf>,
+    },
+}
+
+impl SaveTool {
+    /// 解析 `save` 之后的参数
+    pub fn parse(args: &mut impl Iterator<Item = String>) -> Result<Self, String> {
+        const USAGE: &str = "用法: save inspect <file> | save migrate <file> [out]";
+        let action = args.next().ok_or(USAGE)?;
+        let input: PathBuf = args.next().ok_or(USAGE)?.into();
+        let tool = match action.as_str() {
+            "inspect" => SaveTool::Inspect(input),
+            "migrate" => SaveTool::Migrate {
+                input,
+                output: args.next().map(Into::into),
+            },
+            _ => return Err(USAGE.into()),
+        };
+        match args.next() {
+            Some(extra) => Err(format!("多余的参数: {extra}\n{USAGE}")),
+            None => Ok(tool),
+        }
+    }
+}
+
+/// 执行并返回退出码：0 成功，1 失败
+pub fn run(tool: &SaveTool) -> u8 {
+    let result = match tool {
+        SaveTool::Inspect(path) => inspect(path),
+        SaveTool::Migrate { input, output } => migrate(input, output.as_deref()),
+    };
+    match result {
+        Ok(report) => {
+            println!("{report}");
+            0
+        }
+        Err(err) => {
+            eprintln!("✘ {err}");
+            1
+        }
+    }
+}
+
+fn read(path: &Path) -> Result<(SaveFile, Migrated), String> {
+    let text = std::fs::read_to_string(path).map_err(|err| format!("{}: {err}", path.display()))?;
+    SaveFile::from_json(&text).map_err(|err| format!("{}: {err}", path.display()))
+}
+
+/// 版本、迁移步骤与每个角色的摘要
+pub fn inspect(path: &Path) -> Result<String, String> {
+    let (save, migrated) = read(path)?;
+    let mut out = format!("{}: 存档版本 v{}", path.display(), migrated.from);
+    if migrated.steps.is_empty() {
+        out.push_str("（当前版本）");
+    } else {
+        out.push_str(&format!("，读取时升级到 v{SAVE_VERSION}"));
+        for (idx, step) in migrated.steps.iter().enumerate() {
+            let from = migrated.from as usize + idx;
+            out.push_str(&format!("\n  v{from} → v{}: {step}", from + 1));
+        }
+    }
+
+    out.push_str(&format!("\n{} 个角色", save.actors.len()));
+    for (idx, actor) in save.actors.iter().enumerate() {
+        let who = match (&actor.name, actor.player) {
+            (_, true) => "玩家".to_string(),
+            (Some(name), false) => name.clone(),
+            (None, false) => format!("角色 #{idx}"),
+        };
+        // 存档只有基础属性，含加成的属性需要物品数据才能算出
+        let b = &actor.base;
+        out.push_str(&format!(
+            "\n  {who} [{:?}] Lv{} EXP {} HP {} 基础 MAX_HP {} ATK {} DEF {} RNG {}",
+            actor.faction, actor.level, actor.exp, actor.hp, b.max_hp, b.atk, b.def, b.rng
+        ));
+
+        let equipped: Vec<String> = [
+            ("head", &actor.equipment.head),
+            ("body", &actor.equipment.body),
+            ("weapon", &actor.equipment.weapon),
+            ("accessory", &actor.equipment.accessory),
+        ]
+        .into_iter()
+        .filter_map(|(slot, item)| item.as_ref().map(|item| format!("{slot}={item}")))
+        .collect();
+        if !equipped.is_empty() {
+            out.push_str(&format!("\n    装备: {}", equipped.join(", ")));
+        }
+
+        if let Some(class) = &actor.class {
+            out.push_str(&format!("\n    职业: {class}"));
+        }
+
+        if actor.skill_points > 0 {
+            out.push_str(&format!("\n    技能点: {}", actor.skill_points));
+        }
+
+        let effects: Vec<String> = actor
+            .effects
+            .iter()
+            .map(|saved| {
+                format!(
+                    "{} ×{}（剩余 {} {}）",
+                    saved.effect.name,
+                    saved.stacks,
+                    saved.remaining,
+                    saved.effect.duration.unit()
+                )
+            })
+            .collect();
+        if !effects.is_empty() {
+            out.push_str(&format!("\n    效果: {}", effects.join(", ")));
+        }
+
+        let modifiers: Vec<String> = actor
+            .modifiers
+            .iter()
+            .map(|saved| format!("{} {} {}", saved.source, saved.stat.as_str(), saved.op))
+            .collect();
+        if !modifiers.is_empty() {
+            out.push_str(&format!("\n    修正: {}", modifiers.join(", ")));
+        }
+
+        let backpack = &actor.backpack;
+        out.push_str(&format!(
+            "\n    背包 {}/{}",
+            backpack.slots.len(),
+            backpack.capacity
+        ));
+        for stack in &backpack.slots {
+            out.push_str(&format!(
+                "\n      [{}] {} x{}",
+                stack.index, stack.item, stack.count
+            ));
+        }
+    }
+    Ok(out)
+}
+
+/// 升级并写出（与游戏内存档相同的 tmp + 改名方式）；已是当前版本且原地升级时不改动文件
+pub fn migrate(input: &Path, output: Option<&Path>) -> Result<String, String> {
+    let (save, migrated) = read(input)?;
+
+    let target = match output {
+        Some(output) => output,
+        None if migrated.steps.is_empty() => {
+            return Ok(format!(
+                "{}: 已是 v{SAVE_VERSION}，无需升级",
+                input.display()
+            ));
+        }
+        None => {
+            let mut backup = input.as_os_str().to_owned();
+            backup.push(".bak");
+            std::fs::copy(input, &backup).map_err(|err| format!("备份失败: {err}"))?;
+            input
+        }
+    };
+    write_json(target, &save).map_err(|err| format!("{}: {err}", target.display()))?;
+    Ok(format!(
+        "✔ {} v{} → v{SAVE_VERSION} 已写入 {}",
+        input.display(),
+        migrated.from,
+        target.display()
+    ))
+}
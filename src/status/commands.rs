@@ -0,0 +1,14 @@
+use super::events::*;
+use crate::interface::command::{CliCommand, CommandArgs, CommandSpec, CommandType};
+
+impl CliCommand for ShowEffects {
+    fn spec() -> CommandSpec {
+        CommandSpec::new("effects", CommandType::System, "查看状态效果").target()
+    }
+
+    fn from_args(args: &CommandArgs) -> Self {
+        Self {
+            target: args.target(),
+        }
+    }
+}
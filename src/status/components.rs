@@ -0,0 +1,116 @@
+use bevy::prelude::*;
+
+use crate::character::modifiers::{ModifierSource, StatModifier, StatModifiers};
+use crate::data::schema::{EffectSpec, Stacking};
+
+/// 角色身上的一个状态效果
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActiveEffect {
+    pub spec: EffectSpec,
+    pub stacks: u32,
+    /// 剩余回合 / tick
+    pub remaining: u32,
+    /// 已经过的回合 / tick，按 `interval` 判断是否发作
+    pub elapsed: u32,
+    /// 施加时的回合；施加它的那条命令不计入持续时间
+    pub applied_turn: u64,
+}
+
+/// 经过一个回合 / tick 的结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Tick {
+    /// 本次是否发作（造成伤害 / 治疗）
+    pub fired: bool,
+    pub expired: bool,
+}
+
+impl ActiveEffect {
+    pub fn new(spec: &EffectSpec, turn: u64) -> Self {
+        Self {
+            spec: spec.clone(),
+            stacks: 1,
+            remaining: spec.duration.amount(),
+            elapsed: 0,
+            applied_turn: turn,
+        }
+    }
+
+    /// 修正来源标签 `effect:<id>`
+    pub fn source(&self) -> ModifierSource {
+        ModifierSource::Effect {
+            id: self.spec.id.clone(),
+            name: self.spec.name.clone(),
+        }
+    }
+
+    /// 按当前层数展开的属性修正
+    pub fn modifiers(&self) -> Vec<StatModifier> {
+        self.spec
+            .modifiers
+            .iter()
+            .map(|m| StatModifier::new(m.stat, m.op.scaled(self.stacks), self.source()))
+            .collect()
+    }
+
+    /// 用当前层数与修正替换 `modifiers` 中该效果的旧修正
+    pub fn sync_modifiers(&self, modifiers: &mut StatModifiers) {
+        modifiers.remove_source(&self.source().to_string());
+        for modifier in self.modifiers() {
+            modifiers.add(modifier);
+        }
+    }
+
+    /// 经过一个回合 / tick
+    pub fn advance(&mut self) -> Tick {
+        self.elapsed += 1;
+        self.remaining = self.remaining.saturating_sub(1);
+        let periodic = self.spec.tick_damage != 0 || self.spec.tick_heal != 0;
+        Tick {
+            fired: periodic && self.elapsed.is_multiple_of(self.spec.interval.max(1)),
+            expired: self.remaining == 0,
+        }
+    }
+}
+
+/// 再次施加同一效果的结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Applied {
+    Added,
+    Refreshed,
+    /// 叠加后的层数
+    Stacked(u32),
+    /// `Unique` 效果已存在
+    Ignored,
+}
+
+/// 角色身上的全部状态效果，按施加顺序
+#[derive(Component, Debug, Clone, Default)]
+pub struct StatusEffects(pub Vec<ActiveEffect>);
+
+impl StatusEffects {
+    pub fn get(&self, id: &str) -> Option<&ActiveEffect> {
+        self.0.iter().find(|effect| effect.spec.id == id)
+    }
+
+    /// 按效果的叠加规则施加；返回后 `get(&spec.id)` 即为生效中的效果
+    pub fn apply(&mut self, spec: &EffectSpec, turn: u64) -> Applied {
+        let Some(effect) = self.0.iter_mut().find(|effect| effect.spec.id == spec.id) else {
+            self.0.push(ActiveEffect::new(spec, turn));
+            return Applied::Added;
+        };
+        if spec.stacking == Stacking::Unique {
+            return Applied::Ignored;
+        }
+
+        // 重新施加时采用新的声明（数据可能已重载），并重新计时
+        let stacks = effect.stacks;
+        *effect = ActiveEffect::new(spec, turn);
+        match spec.stacking {
+            Stacking::Intensity { max } => {
+                effect.stacks = (stacks + 1).min(max.max(1));
+                Applied::Stacked(effect.stacks)
+            }
+            _ => Applied::Refreshed,
+        }
+    }
+}
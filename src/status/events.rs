@@ -0,0 +1,17 @@
+use bevy::prelude::*;
+
+use crate::character::target::Target;
+use crate::data::schema::EffectSpec;
+
+/// 对实体施加状态效果（卷轴等）
+#[derive(Event)]
+pub struct ApplyEffect {
+    pub entity: Entity,
+    pub effect: EffectSpec,
+}
+
+/// 查看状态效果（用于命令行）
+#[derive(Event)]
+pub struct ShowEffects {
+    pub target: Target,
+}
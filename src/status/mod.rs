@@ -0,0 +1,42 @@
+//! 状态效果：卷轴等授予的限时效果
+//!
+//! 效果由物品数据声明（[`EffectSpec`](crate::data::schema::EffectSpec)），按回合（一条游戏命令）
+//! 或 tick（一帧）计时，回合在该命令引发的事件都处理完后才结束；同一效果再次施加时按
+//! `stacking` 刷新、叠层或忽略。
+//! 属性修正以 `effect:<id>` 为来源写入 `StatModifiers`，施加与到期时发送 `RecalculateStats`；
+//! 持续伤害 / 治疗通过 `TakeDamage` / `Heal` 结算
+
+pub mod commands;
+pub mod components;
+pub mod events;
+mod systems;
+
+use crate::core::states::AppState;
+use crate::interface::command::CliAppExt;
+use bevy::prelude::*;
+use events::*;
+use systems::*;
+
+pub struct StatusPlugin;
+
+impl Plugin for StatusPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            // 事件 + CLI 命令：effects
+            .add_event::<ApplyEffect>()
+            .register_cli_command::<ShowEffects>()
+            .add_systems(
+                Update,
+                (tick_frame_effects, apply_effects, show_effects)
+                    .chain()
+                    .run_if(in_state(AppState::InGame)),
+            )
+            // 回合在命令的事件链处理完后结束（早于批处理在 Last 中的空闲判断）
+            .add_systems(
+                PostUpdate,
+                tick_turn_effects
+                    .run_if(in_state(AppState::InGame))
+                    .run_if(turn_settled),
+            );
+    }
+}
@@ -0,0 +1,200 @@
+use super::{components::*, events::*};
+use crate::character::events::{Heal, RecalculateStats, TakeDamage};
+use crate::character::modifiers::StatModifiers;
+use crate::character::target::{Target, Targets};
+use crate::core::{events::LogEvent, resources::GameTurn};
+use crate::data::schema::EffectDuration;
+use crate::interface::{command::CommandRegistry, debug_cli::CliLine};
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+
+/// 施加状态效果：按叠加规则更新，并把效果的属性修正写入 `StatModifiers`
+pub fn apply_effects(
+    mut ev_apply: EventReader<ApplyEffect>,
+    mut ev_recalculate: EventWriter<RecalculateStats>,
+    turn: Res<GameTurn>,
+    targets: Targets,
+    mut query: Query<(&mut StatusEffects, &mut StatModifiers)>,
+) {
+    for ev in ev_apply.read() {
+        let Ok((mut effects, mut modifiers)) = query.get_mut(ev.entity) else {
+            continue;
+        };
+        let label = targets.label(ev.entity);
+        let spec = &ev.effect;
+        let applied = effects.apply(spec, turn.0);
+        let duration = format!("{} {}", spec.duration.amount(), spec.duration.unit());
+
+        let message = match applied {
+            Applied::Added => format!("{label}获得效果 {}（{duration}）", spec.name),
+            Applied::Refreshed => format!("{label}{} 持续时间重置为 {duration}", spec.name),
+            Applied::Stacked(stacks) => {
+                format!("{label}{} 叠加到 {stacks} 层（{duration}）", spec.name)
+            }
+            Applied::Ignored => format!("{label}已有 {}，效果不叠加", spec.name),
+        };
+        crate::interface::debug_cli::queue_game_log(message);
+
+        if applied != Applied::Ignored
+            && let Some(effect) = effects.get(&spec.id)
+        {
+            effect.sync_modifiers(&mut modifiers);
+            ev_recalculate.write(RecalculateStats { entity: ev.entity });
+        }
+    }
+}
+
+/// 本条命令引发的事件都已处理完，可以结束回合；在此之前结算会让效果的日志与命令的日志交错
+pub fn turn_settled(world: &World) -> bool {
+    world.resource::<Events<CliLine>>().is_empty()
+        && world.resource::<Events<LogEvent>>().is_empty()
+        && world.resource::<Events<ApplyEffect>>().is_empty()
+        && world.resource::<Events<RecalculateStats>>().is_empty()
+        && !world.resource::<CommandRegistry>().has_pending_events(world)
+}
+
+/// 结束回合：每条游戏命令的事件链处理完后，回合制效果经过一回合
+pub fn tick_turn_effects(
+    turn: Res<GameTurn>,
+    mut last_turn: Local<u64>,
+    mut writers: TickWriters,
+    targets: Targets,
+    mut query: Query<(Entity, &mut StatusEffects, &mut StatModifiers)>,
+) {
+    if turn.0 == *last_turn {
+        return;
+    }
+    let turns = (*last_turn + 1)..=turn.0;
+    *last_turn = turn.0;
+
+    for (entity, mut effects, mut modifiers) in &mut query {
+        advance_effects(entity, &mut effects, &mut modifiers, &targets, &mut writers, |effect| {
+            match effect.spec.duration {
+                // 施加它的那条命令不计
+                EffectDuration::Turns(_) => turns
+                    .clone()
+                    .filter(|turn| *turn > effect.applied_turn)
+                    .count(),
+                EffectDuration::Ticks(_) => 0,
+            }
+        });
+    }
+}
+
+/// tick 制效果每帧经过一 tick
+pub fn tick_frame_effects(
+    mut writers: TickWriters,
+    targets: Targets,
+    mut query: Query<(Entity, &mut StatusEffects, &mut StatModifiers)>,
+) {
+    for (entity, mut effects, mut modifiers) in &mut query {
+        advance_effects(entity, &mut effects, &mut modifiers, &targets, &mut writers, |effect| {
+            match effect.spec.duration {
+                EffectDuration::Turns(_) => 0,
+                EffectDuration::Ticks(_) => 1,
+            }
+        });
+    }
+}
+
+/// 效果发作与到期时发出的事件
+#[derive(SystemParam)]
+pub struct TickWriters<'w> {
+    damage: EventWriter<'w, TakeDamage>,
+    heal: EventWriter<'w, Heal>,
+    recalculate: EventWriter<'w, RecalculateStats>,
+}
+
+/// 让每个效果经过 `units(effect)` 个回合 / tick：发作时发送 `TakeDamage` / `Heal`，
+/// 到期时移除并撤销属性修正
+fn advance_effects(
+    entity: Entity,
+    effects: &mut StatusEffects,
+    modifiers: &mut StatModifiers,
+    targets: &Targets,
+    writers: &mut TickWriters,
+    units: impl Fn(&ActiveEffect) -> usize,
+) {
+    if effects.0.is_empty() {
+        return;
+    }
+    let label = targets.label(entity);
+    let mut expired = Vec::new();
+
+    for (idx, effect) in effects.0.iter_mut().enumerate() {
+        for _ in 0..units(effect) {
+            let tick = effect.advance();
+            if tick.fired {
+                let stacks = effect.stacks as i32;
+                crate::interface::debug_cli::queue_game_log(format!(
+                    "{label}{} 发作",
+                    effect.spec.name
+                ));
+                if effect.spec.tick_damage != 0 {
+                    writers.damage.write(TakeDamage {
+                        target: Target::Entity(entity),
+                        damage: effect.spec.tick_damage * stacks,
+                    });
+                }
+                if effect.spec.tick_heal != 0 {
+                    writers.heal.write(Heal {
+                        target: Target::Entity(entity),
+                        amount: effect.spec.tick_heal * stacks,
+                    });
+                }
+            }
+            if tick.expired {
+                expired.push(idx);
+                break;
+            }
+        }
+    }
+
+    for idx in expired.into_iter().rev() {
+        let effect = effects.0.remove(idx);
+        crate::interface::debug_cli::queue_game_log(format!(
+            "{label}{} 效果结束",
+            effect.spec.name
+        ));
+        if modifiers.remove_source(&effect.source().to_string()) > 0 {
+            writers.recalculate.write(RecalculateStats { entity });
+        }
+    }
+}
+
+/// 显示状态效果
+pub fn show_effects(
+    mut ev_show: EventReader<ShowEffects>,
+    mut ev_log: EventWriter<LogEvent>,
+    targets: Targets,
+    query: Query<&StatusEffects>,
+) {
+    for ev in ev_show.read() {
+        let resolved = targets.resolve(&ev.target);
+        if resolved.is_empty() {
+            ev_log.write(LogEvent(format!("未找到目标 {}", ev.target)));
+        }
+
+        for entity in resolved {
+            let Ok(effects) = query.get(entity) else {
+                continue;
+            };
+            let label = targets.label(entity);
+            if effects.0.is_empty() {
+                ev_log.write(LogEvent(format!("{label}没有状态效果")));
+                continue;
+            }
+            let mut text = format!("{label}状态效果:");
+            for effect in &effects.0 {
+                text.push_str(&format!(
+                    "\n  {} ×{}，剩余 {} {}",
+                    effect.spec.name,
+                    effect.stacks,
+                    effect.remaining,
+                    effect.spec.duration.unit()
+                ));
+            }
+            ev_log.write(LogEvent(text));
+        }
+    }
+}
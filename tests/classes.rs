@@ -0,0 +1,126 @@
+//! 职业：数据解析、校验、装备类型与职业成长
+
+use bevy_demo::character::components::{BaseStats, Stats};
+use bevy_demo::data::classes::{Classes, EquipType};
+use bevy_demo::data::loader::parse_items;
+use bevy_demo::data::progression::Progression;
+use bevy_demo::data::registry::ItemRegistry;
+use bevy_demo::data::schema::{ArmorSlot, ItemKind};
+
+fn classes(src: &str) -> Classes {
+    bevy::asset::ron::de::from_str(src).expect("valid classes")
+}
+
+fn registry() -> ItemRegistry {
+    ItemRegistry::from_list(
+        &parse_items(
+            r#"(items: [
+                (id: "sword", name: "剑", atk: 5, kind: Weapon(hands: 1)),
+                (id: "axe", name: "巨斧", atk: 9, kind: Weapon(hands: 2)),
+                (id: "mail", name: "锁甲", def: 3, kind: Armor(slot: Body)),
+                (id: "robe", name: "长袍", def: 1, kind: Armor(slot: Body)),
+                (id: "potion", name: "药水", kind: Potion(heal: 10)),
+            ])"#,
+            "test.ron",
+        )
+        .unwrap(),
+    )
+}
+
+#[test]
+fn shipped_classes_are_valid() {
+    let src = std::fs::read_to_string("assets/data/classes.ron").unwrap();
+    let shipped = classes(&src);
+    let items = std::fs::read_to_string("assets/data/items.ron").unwrap();
+    let pack = std::fs::read_to_string("assets/data/packs/frontier.ron").unwrap();
+    let mut list = parse_items(&items, "items.ron").unwrap();
+    list.items.extend(parse_items(&pack, "frontier.ron").unwrap().items);
+    assert_eq!(shipped.validate(&ItemRegistry::from_list(&list)), Vec::<String>::new());
+
+    // 冒险者与没有职业时的默认属性相同
+    let adventurer = shipped.get("adventurer").unwrap();
+    let (stats, default) = (Stats::from(&BaseStats::from(adventurer)), Stats::default());
+    assert_eq!(
+        (stats.hp, stats.max_hp, stats.atk, stats.def, stats.rng, stats.lv),
+        (default.hp, default.max_hp, default.atk, default.def, default.rng, default.lv)
+    );
+    assert_eq!(adventurer.allowed, EquipType::all());
+}
+
+#[test]
+fn defaults_and_base_stats() {
+    let classes = classes(
+        r#"(classes: [(id: "monk", name: "武僧", base: (max_hp: 18, atk: 3, def: 1, rng: 1))])"#,
+    );
+    let monk = classes.get("monk").unwrap();
+    assert_eq!(monk.allowed, EquipType::all());
+    assert!(monk.items.is_empty() && monk.equipped.is_empty() && monk.growth.is_none());
+
+    let base = BaseStats::from(monk);
+    assert_eq!((base.lv, base.exp, base.max_hp, base.atk), (1, 0, 18, 3));
+    let stats = Stats::from(&base);
+    assert_eq!((stats.hp, stats.max_hp), (18, 18));
+}
+
+#[test]
+fn equip_type_of_item_kind() {
+    assert_eq!(EquipType::of(&ItemKind::Weapon { hands: 1 }), Some(EquipType::OneHanded));
+    assert_eq!(EquipType::of(&ItemKind::Weapon { hands: 2 }), Some(EquipType::TwoHanded));
+    assert_eq!(
+        EquipType::of(&ItemKind::Armor { slot: ArmorSlot::Accessory }),
+        Some(EquipType::Accessory)
+    );
+    assert_eq!(EquipType::of(&ItemKind::Potion { heal: 5 }), None);
+}
+
+#[test]
+fn class_growth_replaces_default_growth() {
+    let classes = classes(
+        r#"(classes: [
+            (id: "tank", name: "坦克", base: (max_hp: 30, atk: 1, def: 3, rng: 1), growth: (max_hp: 6)),
+            (id: "plain", name: "平民", base: (max_hp: 20, atk: 2, def: 1, rng: 1)),
+        ])"#,
+    );
+    let base = Progression::default();
+
+    let tank = classes.progression_for(Some("tank"), &base);
+    assert_eq!((tank.total_growth(3).max_hp, tank.total_growth(3).atk), (12, 0));
+    // 只替换成长，经验曲线与等级上限不变
+    assert_eq!(tank.exp_to_next(2), base.exp_to_next(2));
+    assert_eq!(tank.level_cap, base.level_cap);
+
+    for class in [Some("plain"), Some("unknown"), None] {
+        assert_eq!(*classes.progression_for(class, &base), base, "{class:?}");
+    }
+}
+
+#[test]
+fn validation_reports_every_problem() {
+    let classes = classes(
+        r#"(classes: [
+            (id: "a", name: "甲", base: (max_hp: 0, atk: 1, def: 1, rng: 1), allowed: []),
+            (id: "a", name: "乙", base: (max_hp: 10, atk: 1, def: 1, rng: 1),
+             items: [("missing", 1), ("potion", 0)]),
+            (id: "b", name: "丙", base: (max_hp: 10, atk: 1, def: 1, rng: 1),
+             allowed: [OneHanded, Body],
+             equipped: ["axe", "mail", "robe", "potion", "nothing"]),
+        ])"#,
+    );
+    let problems = classes.validate(&registry());
+    assert_eq!(
+        problems,
+        vec![
+            "职业 a 的 max_hp 应为正数，得到 0",
+            "职业 a 的 allowed 为空，无法装备任何物品",
+            "职业 a 重复",
+            "职业 a 的开局物品 missing 不存在",
+            "职业 a 的开局物品 potion 数量为 0",
+            "职业 b 不能装备开局装备 axe（双手武器）",
+            "职业 b 的开局装备 robe 与其它装备同占 body 槽",
+            "职业 b 的开局装备 potion 无法装备",
+            "职业 b 的开局装备 nothing 不存在",
+        ]
+    );
+
+    assert_eq!(Classes::default().validate(&registry()), vec!["没有定义任何职业"]);
+}
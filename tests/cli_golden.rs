@@ -0,0 +1,561 @@
+//! doc/next_step.md §1.4 测试矩阵
+
+mod common;
+
+use bevy::prelude::*;
+use bevy_demo::character::components::Player;
+use bevy_demo::core::resources::GameConfig;
+use bevy_demo::core::rng::{GameRng, RngStream};
+use bevy_demo::data::events::{ItemsReloaded, ReloadData};
+use bevy_demo::data::registry::ItemRegistry;
+use bevy_demo::data::schema::{ItemEntry, ItemKind, ItemList};
+use bevy_demo::interface::debug_cli::CliLine;
+use bevy_demo::interface::replay::{REPLAY_DIVERGED_EXIT_CODE, ReplayFile};
+use bevy_demo::inventory::components::Backpack;
+use bevy_demo::save::events::{LoadGame, SaveGame};
+use bevy_demo::save::schema::{SAVE_VERSION, SaveFile};
+use common::Session;
+use rand::Rng;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// 装备‑卸载：equip → unequip，atk/def 增减一致
+#[test]
+fn equip_unequip() {
+    let mut session = Session::run("equip_unequip");
+    assert_eq!(session.exit, AppExit::Success);
+    session.assert_golden("equip_unequip");
+
+    let stats = session.stats();
+    assert_eq!((stats.atk, stats.def), (2, 1));
+}
+
+/// 升级：gain exp 100 → lv ↑，max_hp 增加
+#[test]
+fn gain_exp_100() {
+    let mut session = Session::run("gain_exp_100");
+    assert_eq!(session.exit, AppExit::Success);
+    session.assert_golden("gain_exp_100");
+
+    let stats = session.stats();
+    assert!(stats.lv > 1);
+    assert!(stats.max_hp > 20);
+}
+
+/// 参数类型错误给出精确提示，且不会误操作到默认槽位
+#[test]
+fn bad_input() {
+    let mut session = Session::run("bad_input");
+    assert_eq!(session.exit, AppExit::from_code(1));
+    session.assert_golden("bad_input");
+
+    assert!(session
+        .transcript
+        .contains(r#"expected integer for <index>, got "x""#));
+    assert_eq!(session.stats().atk, 12);
+}
+
+/// give 失败：未知物品 id 提示相近的候选，目标不存在时报告，背包不变
+#[test]
+fn give_unknown() {
+    let mut session = Session::run("give_unknown");
+    assert_eq!(session.exit, AppExit::Success);
+    session.assert_golden("give_unknown");
+
+    assert!(session.transcript.contains("不存在物品 ID swrd_iron，是否想要: sword_iron"));
+    assert!(session.transcript.contains("不存在物品 ID zzzz\n"));
+    assert!(session.transcript.contains("未找到目标 @nobody"));
+    let world = session.app.world_mut();
+    let backpack = world
+        .query_filtered::<&Backpack, With<Player>>()
+        .single(world)
+        .unwrap();
+    assert!(backpack.slots.iter().all(|s| s.count == 0));
+}
+
+/// give 数量：0 不占空位，堆叠数量溢出时拒绝而不是回绕
+#[test]
+fn give_count() {
+    let mut session = Session::run("give_count");
+    assert_eq!(session.exit, AppExit::Success);
+    session.assert_golden("give_count");
+
+    assert!(session.transcript.contains("数量必须大于 0"));
+    assert!(session.transcript.contains("数量超出上限 4294967295，无法获得"));
+    let world = session.app.world_mut();
+    let backpack = world
+        .query_filtered::<&Backpack, With<Player>>()
+        .single(world)
+        .unwrap();
+    let counts: Vec<u32> = backpack.slots.iter().map(|s| s.count).filter(|&c| c > 0).collect();
+    assert_eq!(counts, [u32::MAX]);
+}
+
+/// 负数的经验 / 伤害 / 治疗量在解析时被拒绝，属性不变
+#[test]
+fn negative_amounts() {
+    let mut session = Session::run("negative_amounts");
+    assert_eq!(session.exit, AppExit::from_code(1));
+    session.assert_golden("negative_amounts");
+
+    let stats = session.stats();
+    assert_eq!((stats.hp, stats.exp), (20, 0));
+}
+
+/// 目标解析：按名字 / 阵营作用于非玩家实体，玩家不受影响
+#[test]
+fn target_actors() {
+    let mut session = Session::run("target_actors");
+    assert_eq!(session.exit, AppExit::from_code(1));
+    session.assert_golden("target_actors");
+
+    assert!(session.transcript.contains("goblin1 死亡！"));
+    assert!(session.transcript.contains("goblin2 死亡！"));
+    let stats = session.stats();
+    assert_eq!((stats.hp, stats.atk), (20, 2));
+}
+
+/// 纯数字名字不会被当成实体 id
+#[test]
+fn target_numeric_name() {
+    let mut session = Session::run("target_numeric_name");
+    assert_eq!(session.exit, AppExit::Success);
+    session.assert_golden("target_numeric_name");
+
+    assert!(session.transcript.contains("42 受到 3 点伤害"));
+    assert!(!session.transcript.contains("未找到目标"));
+}
+
+/// 物品类型分发：防具只能进对应槽，药水可用不可装备，钥匙不消耗
+#[test]
+fn item_kinds() {
+    let mut session = Session::run("item_kinds");
+    assert_eq!(session.exit, AppExit::Success);
+    session.assert_golden("item_kinds");
+
+    assert!(session.transcript.contains("皮甲 只能装备在 body 槽"));
+    let stats = session.stats();
+    // 皮甲 +3，坚守卷轴 +3
+    assert_eq!((stats.hp, stats.def), (20, 7));
+}
+
+/// 热重载：注册表按 id 原地更新定义，背包与已装备物品的句柄不变，装备加成随之重算
+#[test]
+fn hot_reload() {
+    let assets = std::path::PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("hot_reload_assets");
+    std::fs::create_dir_all(assets.join("data")).unwrap();
+    let items = std::fs::read_to_string(
+        std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("assets/data/items.ron"),
+    )
+    .unwrap();
+    std::fs::write(assets.join("data/items.ron"), &items).unwrap();
+    std::fs::write(
+        assets.join("data/manifest.ron"),
+        r#"(items: ["data/items.ron"])"#,
+    )
+    .unwrap();
+
+    let mut session = Session::run_with_assets("hot_reload", Some(&assets));
+    assert_eq!(session.exit, AppExit::Success);
+    session.assert_golden("hot_reload");
+    assert_eq!(session.stats().atk, 12);
+
+    let tuned = items
+        .replace("atk: 10", "atk: 25")
+        .replace("Potion(heal: 25)", "Potion(heal: 40)");
+    assert_ne!(tuned, items);
+    std::fs::write(assets.join("data/items.ron"), tuned).unwrap();
+    session.app.world_mut().send_event(ReloadData);
+
+    let started = Instant::now();
+    let mut reloaded = false;
+    while !reloaded || session.stats().atk == 12 {
+        session.app.update();
+        reloaded |= !session.app.world().resource::<Events<ItemsReloaded>>().is_empty();
+        assert!(started.elapsed() < Duration::from_secs(30), "热重载超时");
+        std::thread::sleep(Duration::from_millis(1));
+    }
+
+    assert_eq!(session.stats().atk, 27);
+    let world = session.app.world_mut();
+    let backpack = world
+        .query_filtered::<&Backpack, With<Player>>()
+        .single(world)
+        .unwrap();
+    let registry = world.resource::<ItemRegistry>();
+    let potion = registry.by_id("potion_heal_small").unwrap();
+    assert!(backpack.slots.iter().any(|s| s.count > 0 && s.item == potion));
+    assert_eq!(registry.get(potion).kind, ItemKind::Potion { heal: 40 });
+}
+
+/// CSV 导入：覆盖已有物品的数值并重算装备加成，新物品可直接获得；坏表整体拒绝
+#[test]
+fn csv_import() {
+    let mut session = Session::run("csv_import");
+    assert_eq!(session.exit, AppExit::Success);
+    session.assert_golden("csv_import");
+
+    assert!(session.transcript.contains("✔ 已导入 tests/fixtures/balance.csv: 新增 1，覆盖 1"));
+    assert!(session.transcript.contains("2:3   [sword_iron] atk 应为整数，得到 \"12x\""));
+    assert_eq!(session.stats().atk, 16);
+}
+
+/// 存档 / 读档：读档替换全部角色，存档文件按 id 引用物品
+#[test]
+fn save_load() {
+    let mut session = Session::run("save_load");
+    assert_eq!(session.exit, AppExit::Success);
+    session.assert_golden("save_load");
+    assert_eq!(session.stats().atk, 12);
+
+    let json = std::fs::read_to_string(session.save_dir.join("slot1.json")).unwrap();
+    let save: SaveFile = serde_json::from_str(&json).unwrap();
+    assert_eq!(save.version, SAVE_VERSION);
+    let [player, goblin] = save.actors.as_slice() else {
+        panic!("expected player + goblin: {json}");
+    };
+    assert!(player.player && !goblin.player);
+    assert_eq!(player.equipment.weapon.as_deref(), Some("sword_iron"));
+    assert_eq!(goblin.name.as_deref(), Some("goblin"));
+    assert_eq!(goblin.backpack.slots[0].item, "armor_leather");
+}
+
+/// 有问题的存档整体拒绝：列出全部问题，世界保持不变
+#[test]
+fn invalid_save_is_rejected() {
+    let mut session = Session::run("save_load");
+    let before = session.snapshot();
+
+    let json = std::fs::read_to_string(session.save_dir.join("slot1.json")).unwrap();
+    let mut save: SaveFile = serde_json::from_str(&json).unwrap();
+    save.actors[0].equipment.weapon = Some("potion_heal_small".into());
+    save.actors[0].backpack.slots[0].item = "sword_gold".into();
+    save.actors[1].player = true;
+    std::fs::write(
+        session.save_dir.join("broken.json"),
+        serde_json::to_string(&save).unwrap(),
+    )
+    .unwrap();
+
+    let registry = session.app.world().resource::<ItemRegistry>();
+    assert_eq!(
+        save.validate(registry),
+        [
+            "存档应包含 1 个玩家，实际 2 个",
+            "玩家 背包 [1]: 未知物品 id sword_gold",
+            "玩家 装备 weapon: potion_heal_small 不能装备在该槽",
+        ]
+    );
+
+    session.app.world_mut().send_event(LoadGame {
+        slot: "broken".into(),
+    });
+    for _ in 0..3 {
+        session.app.update();
+    }
+    assert_eq!(session.snapshot(), before);
+}
+
+/// 旧版本存档读档时自动升级
+#[test]
+fn old_save_is_upgraded_on_load() {
+    let mut session = Session::run("save_load");
+    std::fs::copy("tests/fixtures/saves/v1.json", session.save_dir.join("old.json")).unwrap();
+
+    session.app.world_mut().send_event(LoadGame { slot: "old".into() });
+    for _ in 0..3 {
+        session.app.update();
+    }
+    let stats = session.stats();
+    assert_eq!((stats.lv, stats.exp, stats.hp, stats.atk), (3, 50, 15, 14));
+}
+
+/// 自动存档：退出时写入最终状态，写入过程不留下临时文件，会话标记被删除
+#[test]
+fn autosave_on_exit() {
+    let mut session = Session::run("save_load");
+    let dir = session.save_dir.clone();
+    assert!(!dir.join(".session").exists());
+    assert!(dir.join("autosave-1.json").exists(), "升级与第 10 条游戏命令触发过自动存档");
+
+    let json = std::fs::read_to_string(dir.join("autosave.json")).unwrap();
+    let (save, _) = SaveFile::from_json(&json).unwrap();
+    let stats = session.stats();
+    assert_eq!((save.actors[0].level, save.actors[0].hp), (stats.lv, stats.hp));
+    assert_eq!(save.actors.len(), 2);
+
+    let leftovers: Vec<_> = std::fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .filter(|name| name.ends_with(".tmp"))
+        .collect();
+    assert!(leftovers.is_empty(), "{leftovers:?}");
+}
+
+/// 读档不沿用存档里的派生属性：装备加成按读档时的物品数据重算
+#[test]
+fn load_recomputes_stats_from_current_data() {
+    let mut session = Session::run("save_load");
+    assert_eq!(session.stats().atk, 12);
+
+    let world = session.app.world_mut();
+    let mut registry = world.resource_mut::<ItemRegistry>();
+    let items = registry
+        .iter()
+        .map(|(_, entry)| match entry.id.as_str() {
+            "sword_iron" => ItemEntry { atk: 30, ..entry.clone() },
+            _ => entry.clone(),
+        })
+        .collect();
+    registry.reload(&ItemList {
+        items,
+        source: String::new(),
+        spans: Vec::new(),
+        hash: String::new(),
+    });
+    world.send_event(LoadGame { slot: "slot1".into() });
+    for _ in 0..4 {
+        session.app.update();
+    }
+
+    let stats = session.stats();
+    assert_eq!((stats.atk, stats.hp, stats.max_hp), (32, 15, 20));
+}
+
+/// 增益修正随存档保存：buff → save → load → equip 后仍在明细中
+#[test]
+fn buff_save_load() {
+    let mut session = Session::run("buff_save_load");
+    assert_eq!(session.exit, AppExit::Success);
+    session.assert_golden("buff_save_load");
+
+    // 存档之后添加的光环随读档消失
+    let detail = session.transcript.split("> stats --detail").nth(1).unwrap();
+    assert!(detail.contains("增益 rage（+5）"), "{detail}");
+    assert!(!detail.contains("光环 guard"), "{detail}");
+    let stats = session.stats();
+    assert_eq!((stats.atk, stats.def), (17, 1));
+}
+
+/// 自动存档轮换：只保留 `autosave_backups` 份历史
+#[test]
+fn autosave_rotates_backups() {
+    let mut session = Session::run("save_load");
+    session.app.world_mut().resource_mut::<GameConfig>().autosave_every = 1;
+    session.app.world_mut().resource_mut::<GameConfig>().autosave_backups = 2;
+    for _ in 0..4 {
+        session.app.world_mut().send_event(CliLine("heal 1".into()));
+        for _ in 0..4 {
+            session.app.update();
+        }
+    }
+
+    let dir = &session.save_dir;
+    assert!(dir.join("autosave-1.json").exists());
+    assert!(dir.join("autosave-2.json").exists());
+    assert!(!dir.join("autosave-3.json").exists());
+}
+
+/// 上次没有正常退出：启动时提示读取自动存档
+#[test]
+fn autosave_resume() {
+    let dir = Session::save_dir_of("autosave_resume");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join(".session"), "0").unwrap();
+    let current = format!("tests/fixtures/saves/v{SAVE_VERSION}.json");
+    std::fs::copy(current, dir.join("autosave.json")).unwrap();
+
+    let mut session = Session::run_resuming("autosave_resume");
+    assert_eq!(session.exit, AppExit::Success);
+    session.assert_golden("autosave_resume");
+    assert!(!dir.join(".session").exists());
+}
+
+/// 随机数状态随存档保存：读档后随机序列从存档处继续
+#[test]
+fn save_restores_rng() {
+    let mut session = Session::run("save_load");
+    let draw = |session: &mut Session| -> Vec<u32> {
+        let mut rng = session.app.world_mut().resource_mut::<GameRng>();
+        (0..4).map(|_| rng.stream(RngStream::Combat).gen_range(0..1000)).collect()
+    };
+
+    draw(&mut session);
+    session.app.world_mut().send_event(SaveGame { slot: "rng".into() });
+    session.app.update();
+    let after_save = draw(&mut session);
+    draw(&mut session);
+
+    session.app.world_mut().send_event(LoadGame { slot: "rng".into() });
+    session.app.update();
+    assert_eq!(draw(&mut session), after_save);
+}
+
+/// 录制 `save_load` 会话，返回回放文件路径
+fn record_save_load(name: &str) -> PathBuf {
+    let path = PathBuf::from(env!("CARGO_TARGET_TMPDIR"))
+        .join("replays")
+        .join(format!("{name}.json"));
+    let record = path.clone();
+    let session = Session::run_with("save_load", |args| args.record = Some(record));
+    assert_eq!(session.exit, AppExit::Success);
+    path
+}
+
+fn replay(path: &Path) -> Session {
+    let path = path.to_path_buf();
+    Session::run_with("save_load", |args| {
+        args.script = None;
+        args.replay = Some(path);
+        args.verify = true;
+    })
+}
+
+/// 录制：每条被接受的命令、种子、数据文件哈希；回放后状态一致
+#[test]
+fn record_and_replay() {
+    let path = record_save_load("match");
+    let file = ReplayFile::read(&path).unwrap();
+    assert_eq!(file.commands.len(), 20);
+    assert_eq!(file.commands[0].line, "give sword_iron");
+    assert!(file.commands.windows(2).all(|w| w[0].tick < w[1].tick));
+    assert_eq!(file.class.as_deref(), Some("adventurer"));
+    assert!(file.data.iter().any(|d| d.path == "data/items.ron"));
+    assert!(file.final_tick.is_some() && file.final_state.is_some());
+
+    let mut session = replay(&path);
+    assert_eq!(session.exit, AppExit::Success, "{}", session.transcript);
+    assert!(session.transcript.contains("✔ 回放一致：20 条命令"));
+    assert_eq!(session.stats().atk, 12);
+    assert_eq!(
+        session.app.world().resource::<GameRng>().seed(),
+        file.seed
+    );
+}
+
+/// 回放与录制不一致：报告第一次分歧与数据文件差异，退出码 4
+#[test]
+fn replay_reports_first_divergence() {
+    let path = record_save_load("diverge");
+    let mut file = ReplayFile::read(&path).unwrap();
+    let damage = file
+        .commands
+        .iter()
+        .position(|c| c.line == "take_damage 5")
+        .unwrap();
+    file.commands[damage].line = "take_damage 6".into();
+    file.data[0].hash = "0000000000000000".into();
+    std::fs::write(&path, serde_json::to_string(&file).unwrap()).unwrap();
+
+    let session = replay(&path);
+    assert_eq!(session.exit, AppExit::from_code(REPLAY_DIVERGED_EXIT_CODE));
+    assert!(session.transcript.contains("⚠ 数据文件 data/items.ron 与录制时不同"));
+    assert!(
+        session.transcript.contains(&format!(
+            "第 {} 条命令 `{}`（tick {}）执行前状态",
+            damage + 2,
+            file.commands[damage + 1].line,
+            file.commands[damage + 1].tick
+        )),
+        "{}",
+        session.transcript
+    );
+    assert!(session.transcript.contains("上一条命令: `take_damage 6`"));
+}
+
+/// v1 回放没有记录职业：按冒险者开局（录制时的默认属性），照常回放
+#[test]
+fn replay_v1_starts_as_adventurer() {
+    let path = PathBuf::from("tests/fixtures/replays/v1.json");
+    let file = ReplayFile::read(&path).unwrap();
+    assert_eq!(file.class.as_deref(), Some("adventurer"));
+
+    let mut session = Session::run_with("save_load", |args| {
+        args.script = None;
+        args.class = None;
+        args.replay = Some(path);
+    });
+    assert_eq!(session.exit, AppExit::Success, "{}", session.transcript);
+    assert!(!session.transcript.contains("职业列表"), "{}", session.transcript);
+    assert_eq!(session.stats().atk, 12);
+    assert_eq!(session.app.world().resource::<GameRng>().seed(), 7);
+}
+
+/// 属性修正：升级后装备仍保留等级加成，`stats --detail` 列出每项来源，移除来源后还原
+#[test]
+fn stat_breakdown() {
+    let mut session = Session::run("stat_breakdown");
+    assert_eq!(session.exit, AppExit::Success);
+    session.assert_golden("stat_breakdown");
+
+    assert!(session.transcript.contains("装备 weapon 粗铁剑（+10）"));
+    assert!(session.transcript.contains("来源应为 buff:<名字> 或 aura:<名字>"));
+    let stats = session.stats();
+    assert_eq!((stats.lv, stats.atk, stats.def), (2, 13, 6));
+}
+
+/// 状态效果：卷轴授予效果，回合结束时发作，到期后属性修正撤销
+#[test]
+fn status_effects() {
+    let mut session = Session::run("status_effects");
+    assert_eq!(session.exit, AppExit::Success);
+    session.assert_golden("status_effects");
+
+    assert!(session.transcript.contains("goblin 已有 中毒，效果不叠加"));
+    assert!(session.transcript.contains("goblin 中毒 效果结束"));
+    // 第二张坚守卷轴仍剩 1 回合
+    assert_eq!(session.stats().def, 4);
+}
+
+/// 成长曲线：一次升到 Lv5，途经等级的成长合计，Lv5 发放物品与技能点
+#[test]
+fn level_rewards() {
+    let mut session = Session::run("level_rewards");
+    assert_eq!(session.exit, AppExit::Success);
+    session.assert_golden("level_rewards");
+
+    assert!(session.transcript.contains("升级！等级 1 → 5，生命值 +11，攻击力 +5，防御力 +4"));
+    assert!(session.transcript.contains("获得 Lv5 奖励 小型治疗药水 ×2"));
+    let stats = session.stats();
+    assert_eq!((stats.lv, stats.max_hp, stats.atk, stats.def), (5, 31, 7, 5));
+}
+
+/// 先升级后装备与先装备后升级得到相同的属性，基础属性只记录等级进度
+#[test]
+fn level_and_equip_in_either_order() {
+    let mut results = Vec::new();
+    for case in ["level_then_equip", "equip_then_level"] {
+        let mut session = Session::run(case);
+        assert_eq!(session.exit, AppExit::Success);
+        session.assert_golden(case);
+
+        assert!(session.transcript.contains("等级 Lv3"), "{case}");
+        let stats = session.stats();
+        assert_eq!((stats.lv, stats.max_hp, stats.atk, stats.def), (3, 24, 14, 3), "{case}");
+        // 升级满血后装备不影响生命值
+        assert_eq!(stats.hp, 24, "{case}");
+        let base = session.base_stats();
+        assert_eq!((base.lv, base.exp, base.max_hp, base.atk, base.def), (3, 50, 20, 2, 1), "{case}");
+        results.push(session.snapshot().split("--- final state ---").nth(1).unwrap().to_string());
+    }
+    assert_eq!(results[0], results[1]);
+}
+
+/// 职业选择：开局前拒绝游戏命令，法师带开局物品、不能装备武器、按职业成长
+#[test]
+fn new_game() {
+    let mut session = Session::run_with("new_game", |args| args.class = None);
+    assert_eq!(session.exit, AppExit::from_code(1));
+    session.assert_golden("new_game");
+
+    assert!(session.transcript.contains("游戏尚未开始，`stats` 暂不可用"));
+    assert!(session.transcript.contains("未知职业 knight"));
+    assert!(session.transcript.contains("法师不能使用单手武器"));
+    assert!(session.transcript.contains("游戏已经开始，不能重新选择职业"));
+    let base = session.base_stats();
+    assert_eq!((base.lv, base.max_hp, base.atk, base.def, base.rng), (3, 16, 1, 0, 4));
+    let stats = session.stats();
+    // 法师每级生命值 +1、攻击力 +1，头盔 max_hp +5 def +1
+    assert_eq!((stats.max_hp, stats.atk, stats.def), (23, 3, 1));
+}
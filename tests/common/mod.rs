@@ -0,0 +1,204 @@
+//! CLI 会话黄金文件测试工具
+//!
+//! 用真实插件在进程内组装 `App`，把 `tests/golden/<case>.cli` 作为批处理脚本逐行注入
+//! `CliLine`，逐帧推进直到脚本执行完毕且事件队列空闲，然后把 transcript 与最终
+//! `Stats` / `Backpack` / `Equipment` 状态与 `tests/golden/<case>.golden` 比较。
+//!
+//! 设置 `UPDATE_GOLDEN=1` 重新生成黄金文件。
+
+use bevy::prelude::*;
+use bevy::state::app::StatesPlugin;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use bevy_demo::add_game_plugins;
+use bevy_demo::character::components::{BaseStats, Player, Stats};
+use bevy_demo::core::args::{LaunchArgs, ScriptSource};
+use bevy_demo::core::resources::GameConfig;
+use bevy_demo::data::registry::ItemRegistry;
+use bevy_demo::equipment::components::Equipment;
+use bevy_demo::inventory::components::Backpack;
+
+/// CLI 输出队列是进程级全局的，同一时间只能跑一个会话
+static SESSION_LOCK: Mutex<()> = Mutex::new(());
+
+const SESSION_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// 一次 CLI 会话的结果；持有会话锁，脚本结束后仍可继续推进 `app`
+pub struct Session {
+    pub exit: AppExit,
+    pub transcript: String,
+    pub app: App,
+    /// 本会话的存档目录（每次运行前清空）
+    pub save_dir: PathBuf,
+    _guard: MutexGuard<'static, ()>,
+}
+
+impl Session {
+    /// 运行 `tests/golden/<case>.cli`
+    pub fn run(case: &str) -> Self {
+        Self::run_with_assets(case, None)
+    }
+
+    /// 同上，但从 `assets` 目录加载数据（缺省为仓库的 `assets/`）
+    pub fn run_with_assets(case: &str, assets: Option<&Path>) -> Self {
+        Self::start(case, assets, true, |_| {})
+    }
+
+    /// 调整启动参数后运行（如录制 / 回放）
+    pub fn run_with(case: &str, configure: impl FnOnce(&mut LaunchArgs)) -> Self {
+        Self::start(case, None, true, configure)
+    }
+
+    /// 保留 [`Session::save_dir_of`] 中已有的文件运行，模拟上次会话留下的存档
+    pub fn run_resuming(case: &str) -> Self {
+        Self::start(case, None, false, |_| {})
+    }
+
+    /// 会话 `case` 使用的存档目录
+    pub fn save_dir_of(case: &str) -> PathBuf {
+        PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("saves").join(case)
+    }
+
+    fn start(
+        case: &str,
+        assets: Option<&Path>,
+        fresh_saves: bool,
+        configure: impl FnOnce(&mut LaunchArgs),
+    ) -> Self {
+        let guard = SESSION_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+
+        let transcript_path =
+            PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(format!("{case}.transcript"));
+
+        let asset_plugin = match assets {
+            Some(path) => AssetPlugin {
+                file_path: path.display().to_string(),
+                ..default()
+            },
+            None => AssetPlugin::default(),
+        };
+
+        let save_dir = Self::save_dir_of(case);
+        if fresh_saves {
+            let _ = std::fs::remove_dir_all(&save_dir);
+        }
+
+        // 默认以冒险者开局（与没有职业时的属性相同），脚本不必先选职业
+        let mut args = LaunchArgs {
+            headless: true,
+            class: Some("adventurer".into()),
+            script: Some(ScriptSource::File(golden_dir().join(format!("{case}.cli")))),
+            transcript: Some(transcript_path.clone()),
+            ..default()
+        };
+        configure(&mut args);
+
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, asset_plugin, StatesPlugin))
+            .insert_resource(GameConfig {
+                save_dir: save_dir.clone(),
+                ..default()
+            })
+            .insert_resource(args);
+        add_game_plugins(&mut app);
+
+        let started = Instant::now();
+        let exit = loop {
+            app.update();
+            if let Some(exit) = app.should_exit() {
+                break exit;
+            }
+            assert!(
+                started.elapsed() < SESSION_TIMEOUT,
+                "会话 {case} 超时未结束"
+            );
+            // 资产在后台线程加载，避免空转
+            std::thread::sleep(Duration::from_millis(1));
+        };
+
+        let transcript = std::fs::read_to_string(&transcript_path).unwrap_or_default();
+        Self {
+            exit,
+            transcript,
+            app,
+            save_dir,
+            _guard: guard,
+        }
+    }
+
+    /// 玩家最终属性
+    pub fn stats(&mut self) -> Stats {
+        self.app
+            .world_mut()
+            .query_filtered::<&Stats, With<Player>>()
+            .single(self.app.world())
+            .expect("player must exist")
+            .clone()
+    }
+
+    /// 玩家最终的基础属性与等级进度
+    pub fn base_stats(&mut self) -> BaseStats {
+        self.app
+            .world_mut()
+            .query_filtered::<&BaseStats, With<Player>>()
+            .single(self.app.world())
+            .expect("player must exist")
+            .clone()
+    }
+
+    /// transcript + 最终状态快照，即黄金文件内容
+    pub fn snapshot(&mut self) -> String {
+        let stats = self.stats();
+        let world = self.app.world_mut();
+        let (backpack, equipment) = world
+            .query_filtered::<(&Backpack, &Equipment), With<Player>>()
+            .single(world)
+            .expect("player must have backpack and equipment");
+
+        let registry = world.resource::<ItemRegistry>();
+        let mut out = self.transcript.clone();
+        out.push_str("--- final state ---\n");
+        out.push_str(&format!(
+            "stats: hp={}/{} atk={} def={} lv={} exp={} rng={}\n",
+            stats.hp, stats.max_hp, stats.atk, stats.def, stats.lv, stats.exp, stats.rng
+        ));
+        out.push_str("backpack:\n");
+        for (idx, stack) in backpack.slots.iter().enumerate() {
+            if stack.count > 0 {
+                out.push_str(&format!("  [{idx}] {} x{}\n", registry.get(stack.item).id, stack.count));
+            }
+        }
+        out.push_str("equipment:\n");
+        for slot in Equipment::all_slots() {
+            let item = equipment
+                .get_slot(slot)
+                .map_or("-", |s| registry.get(s.item).id.as_str());
+            out.push_str(&format!("  {slot}: {item}\n"));
+        }
+        out
+    }
+
+    /// 与 `tests/golden/<case>.golden` 比较
+    pub fn assert_golden(&mut self, case: &str) {
+        let actual = self.snapshot();
+        let path = golden_dir().join(format!("{case}.golden"));
+
+        if std::env::var_os("UPDATE_GOLDEN").is_some() {
+            std::fs::write(&path, &actual).unwrap();
+            return;
+        }
+
+        let expected = std::fs::read_to_string(&path)
+            .unwrap_or_else(|e| panic!("无法读取 {}: {e}（UPDATE_GOLDEN=1 生成）", path.display()));
+        assert_eq!(
+            actual, expected,
+            "会话 {case} 与黄金文件不一致（UPDATE_GOLDEN=1 更新）"
+        );
+    }
+}
+
+fn golden_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/golden")
+}
@@ -0,0 +1,311 @@
+//! 数据加载：清单 + 多个物品文件（RON / JSON / TOML）按顺序合并；缺文件 / 语法错误 / 校验不通过
+//! 都进入 LoadFailed 并以非零退出码结束
+
+use bevy::prelude::*;
+use bevy::state::app::StatesPlugin;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use bevy_demo::add_data_plugins;
+use bevy_demo::core::args::LaunchArgs;
+use bevy_demo::core::states::AppState;
+use bevy_demo::data::classes::Classes;
+use bevy_demo::data::progression::Progression;
+use bevy_demo::data::registry::ItemRegistry;
+use bevy_demo::data::{LOAD_FAILED_EXIT_CODE, LoadFailure};
+
+const MANIFEST: &str = r#"(items: ["data/items.ron"])"#;
+
+/// 把 `files`（相对 assets 根目录的路径 → 内容）写入临时目录，跑一次 `--validate-data`
+fn run(case: &str, files: &[(&str, &str)]) -> (AppExit, Option<String>, App) {
+    let root = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(format!("data_loading/{case}"));
+    let _ = std::fs::remove_dir_all(&root);
+    for (path, content) in files {
+        let path = root.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        AssetPlugin {
+            file_path: root.display().to_string(),
+            ..default()
+        },
+        StatesPlugin,
+    ))
+    .insert_resource(LaunchArgs {
+        headless: true,
+        validate_data: true,
+        ..default()
+    });
+    add_data_plugins(&mut app);
+
+    let started = Instant::now();
+    let exit = loop {
+        app.update();
+        if let Some(exit) = app.should_exit() {
+            break exit;
+        }
+        assert!(started.elapsed() < Duration::from_secs(30), "{case} 超时未结束");
+        std::thread::sleep(Duration::from_millis(1));
+    };
+
+    let failure = app.world().get_resource::<LoadFailure>().map(|f| f.0.clone());
+    if failure.is_some() {
+        assert_eq!(*app.world().resource::<State<AppState>>().get(), AppState::LoadFailed);
+    }
+    (exit, failure, app)
+}
+
+#[test]
+fn valid_data_exits_cleanly() {
+    let (exit, failure, _) = run(
+        "valid",
+        &[
+            ("data/manifest.ron", MANIFEST),
+            ("data/items.ron", r#"(items: [(id: "potion", name: "药水", kind: Potion(heal: 10))])"#),
+        ],
+    );
+    assert_eq!(exit, AppExit::Success);
+    assert_eq!(failure, None);
+}
+
+#[test]
+fn later_packs_add_and_override_by_id() {
+    let (exit, failure, app) = run(
+        "packs",
+        &[
+            (
+                "data/manifest.ron",
+                r#"(items: ["data/base.ron", "data/mods/a.ron", "data/mods/b.ron"])"#,
+            ),
+            (
+                "data/base.ron",
+                r#"(items: [
+                    (id: "sword", name: "剑", atk: 10, kind: Weapon()),
+                    (id: "potion", name: "药水", kind: Potion(heal: 10)),
+                ])"#,
+            ),
+            (
+                "data/mods/a.ron",
+                r#"(items: [
+                    (id: "ring", name: "戒指", rng: 1, kind: Armor(slot: Accessory)),
+                    (id: "SWORD", name: "利剑", atk: 15, kind: Weapon()),
+                ])"#,
+            ),
+            (
+                "data/mods/b.ron",
+                r#"(items: [(id: "sword", name: "神剑", atk: 30, kind: Weapon(hands: 2))])"#,
+            ),
+        ],
+    );
+    assert_eq!(exit, AppExit::Success, "{failure:?}");
+
+    let world = app.world();
+    let registry = world.resource::<ItemRegistry>();
+    let summary: Vec<_> = registry
+        .iter()
+        .map(|(_, e)| (e.id.as_str(), e.name.as_str(), e.atk))
+        .collect();
+    // 覆盖保留原位置，新条目按清单顺序追加，最后加载的文件生效
+    assert_eq!(
+        summary,
+        [("sword", "神剑", 30), ("potion", "药水", 0), ("ring", "戒指", 0)]
+    );
+}
+
+#[test]
+fn missing_manifest_fails() {
+    let (exit, failure, _) = run("no_manifest", &[]);
+    assert_eq!(exit, AppExit::from_code(LOAD_FAILED_EXIT_CODE));
+    assert!(failure.unwrap().starts_with("data/manifest.ron: "));
+}
+
+#[test]
+fn missing_item_file_fails() {
+    let (exit, failure, _) = run("missing", &[("data/manifest.ron", MANIFEST)]);
+    assert_eq!(exit, AppExit::from_code(LOAD_FAILED_EXIT_CODE));
+    assert!(failure.unwrap().starts_with("data/items.ron: "));
+}
+
+#[test]
+fn malformed_ron_surfaces_loader_error() {
+    let (exit, failure, _) = run(
+        "malformed",
+        &[
+            ("data/manifest.ron", MANIFEST),
+            ("data/items.ron", r#"(items: [(id: "potion", name: )])"#),
+        ],
+    );
+    assert_eq!(exit, AppExit::from_code(LOAD_FAILED_EXIT_CODE));
+    let failure = failure.unwrap();
+    assert!(failure.contains("Could not parse RON"), "{failure}");
+}
+
+#[test]
+fn invalid_data_fails_with_report() {
+    let (exit, failure, _) = run(
+        "invalid",
+        &[
+            ("data/manifest.ron", MANIFEST),
+            ("data/items.ron", r#"(items: [(id: "potion", name: "", kind: Potion(heal: -1))])"#),
+        ],
+    );
+    assert_eq!(exit, AppExit::from_code(LOAD_FAILED_EXIT_CODE));
+    let failure = failure.unwrap();
+    assert!(failure.contains("[potion] 物品名称为空"), "{failure}");
+    assert!(failure.contains("[potion] heal 不能为负数: -1"), "{failure}");
+}
+
+#[test]
+fn json_and_toml_files_load_by_extension() {
+    let (exit, failure, app) = run(
+        "formats",
+        &[
+            (
+                "data/manifest.ron",
+                r#"(items: ["data/items.ron", "data/balance.json", "data/hand.toml"])"#,
+            ),
+            ("data/items.ron", r#"(items: [(id: "sword", name: "剑", atk: 10, kind: Weapon())])"#),
+            (
+                "data/balance.json",
+                r#"{"items": [{"id": "sword", "name": "剑", "atk": 12, "kind": {"Weapon": {}}}]}"#,
+            ),
+            (
+                "data/hand.toml",
+                "[[items]]\nid = \"potion\"\nname = \"药水\"\nkind = { Potion = { heal = 10 } }\n",
+            ),
+        ],
+    );
+    assert_eq!(exit, AppExit::Success, "{failure:?}");
+
+    let registry = app.world().resource::<ItemRegistry>();
+    let summary: Vec<_> = registry.iter().map(|(_, e)| (e.id.as_str(), e.atk)).collect();
+    assert_eq!(summary, [("sword", 12), ("potion", 0)]);
+}
+
+#[test]
+fn malformed_json_and_toml_surface_loader_error() {
+    for (file, content, expected) in [
+        ("data/items.json", r#"{"items": [{"id": "potion", "name": }]}"#, "Could not parse JSON"),
+        ("data/items.toml", "[[items]]\nid = \"potion\"\nname =\n", "Could not parse TOML"),
+    ] {
+        let manifest = format!(r#"(items: ["{file}"])"#);
+        let (exit, failure, _) = run(
+            &format!("malformed_{}", &file[11..]),
+            &[("data/manifest.ron", &manifest), (file, content)],
+        );
+        assert_eq!(exit, AppExit::from_code(LOAD_FAILED_EXIT_CODE));
+        let failure = failure.unwrap();
+        assert!(failure.starts_with(file), "{failure}");
+        assert!(failure.contains(expected), "{failure}");
+    }
+}
+
+#[test]
+fn csv_files_load_and_report_cells() {
+    let manifest = r#"(items: ["data/items.ron", "data/balance.csv"])"#;
+    let base = r#"(items: [(id: "sword", name: "剑", atk: 10, kind: Weapon())])"#;
+
+    let (exit, failure, app) = run(
+        "csv",
+        &[
+            ("data/manifest.ron", manifest),
+            ("data/items.ron", base),
+            ("data/balance.csv", "id,name,atk,item_type\nsword,剑,11,weapon\n"),
+        ],
+    );
+    assert_eq!(exit, AppExit::Success, "{failure:?}");
+    let registry = app.world().resource::<ItemRegistry>();
+    assert_eq!(registry.get(registry.by_id("sword").unwrap()).atk, 11);
+
+    let (exit, failure, _) = run(
+        "csv_bad",
+        &[
+            ("data/manifest.ron", manifest),
+            ("data/items.ron", base),
+            ("data/balance.csv", "id,name,atk,item_type\nsword,剑,11,sword\n"),
+        ],
+    );
+    assert_eq!(exit, AppExit::from_code(LOAD_FAILED_EXIT_CODE));
+    let failure = failure.unwrap();
+    assert!(failure.starts_with("data/balance.csv: "), "{failure}");
+    assert!(failure.contains("Could not parse CSV: data/balance.csv: 1 个问题"), "{failure}");
+    assert!(failure.contains("2:4   [sword] 未知的 item_type \"sword\""), "{failure}");
+}
+
+#[test]
+fn progression_listed_in_manifest_is_loaded_and_validated() {
+    let manifest = r#"(items: ["data/items.ron"], progression: "data/progression.ron")"#;
+    let items = r#"(items: [(id: "potion", name: "药水", kind: Potion(heal: 10))])"#;
+
+    let (exit, failure, app) = run(
+        "progression",
+        &[
+            ("data/manifest.ron", manifest),
+            ("data/items.ron", items),
+            (
+                "data/progression.ron",
+                r#"(level_cap: 3, exp: Table([5]), growth: (atk: 2), rewards: [(level: 3, items: [("potion", 1)])])"#,
+            ),
+        ],
+    );
+    assert_eq!(exit, AppExit::Success, "{failure:?}");
+    let progression = app.world().resource::<Progression>();
+    assert_eq!((progression.level_cap, progression.exp_to_next(2)), (3, Some(5)));
+
+    let (exit, failure, _) = run(
+        "progression_bad",
+        &[
+            ("data/manifest.ron", manifest),
+            ("data/items.ron", items),
+            (
+                "data/progression.ron",
+                r#"(level_cap: 3, exp: Formula(base: 10, exponent: 2), growth: (), rewards: [(level: 2, items: [("elixir", 1)])])"#,
+            ),
+        ],
+    );
+    assert_eq!(exit, AppExit::from_code(LOAD_FAILED_EXIT_CODE));
+    let failure = failure.unwrap();
+    assert!(failure.starts_with("data/progression.ron: 成长曲线校验失败"), "{failure}");
+    assert!(failure.contains("等级 2 的奖励物品 elixir 不存在"), "{failure}");
+}
+
+#[test]
+fn classes_listed_in_manifest_are_loaded_and_validated() {
+    let manifest = r#"(items: ["data/items.ron"], classes: "data/classes.ron")"#;
+    let items = r#"(items: [(id: "potion", name: "药水", kind: Potion(heal: 10))])"#;
+
+    let (exit, failure, app) = run(
+        "classes",
+        &[
+            ("data/manifest.ron", manifest),
+            ("data/items.ron", items),
+            (
+                "data/classes.ron",
+                r#"(classes: [(id: "monk", name: "武僧", base: (max_hp: 18, atk: 3, def: 1, rng: 1), items: [("potion", 2)])])"#,
+            ),
+        ],
+    );
+    assert_eq!(exit, AppExit::Success, "{failure:?}");
+    let classes = app.world().resource::<Classes>();
+    assert_eq!(classes.get("monk").map(|class| class.base.max_hp), Some(18));
+
+    let (exit, failure, _) = run(
+        "classes_bad",
+        &[
+            ("data/manifest.ron", manifest),
+            ("data/items.ron", items),
+            (
+                "data/classes.ron",
+                r#"(classes: [(id: "monk", name: "武僧", base: (max_hp: 18, atk: 3, def: 1, rng: 1), equipped: ["potion"])])"#,
+            ),
+        ],
+    );
+    assert_eq!(exit, AppExit::from_code(LOAD_FAILED_EXIT_CODE));
+    let failure = failure.unwrap();
+    assert!(failure.starts_with("data/classes.ron: 职业校验失败"), "{failure}");
+    assert!(failure.contains("职业 monk 的开局装备 potion 无法装备"), "{failure}");
+}
@@ -0,0 +1,60 @@
+//! 随机数：同种子可复现、子流互不影响、状态可保存恢复
+
+use bevy_demo::core::args::LaunchArgs;
+use bevy_demo::core::rng::{GameRng, RngStream};
+use rand::Rng;
+
+fn draws(rng: &mut GameRng, stream: RngStream, n: usize) -> Vec<u32> {
+    (0..n).map(|_| rng.stream(stream).gen_range(0..1000)).collect()
+}
+
+#[test]
+fn same_seed_same_sequence() {
+    let (mut a, mut b) = (GameRng::new(123), GameRng::new(123));
+    assert_eq!(draws(&mut a, RngStream::Combat, 8), draws(&mut b, RngStream::Combat, 8));
+    assert_ne!(
+        draws(&mut GameRng::new(123), RngStream::Loot, 8),
+        draws(&mut GameRng::new(124), RngStream::Loot, 8)
+    );
+}
+
+#[test]
+fn streams_are_independent() {
+    let mut quiet = GameRng::new(7);
+    let mut busy = GameRng::new(7);
+    draws(&mut busy, RngStream::Combat, 100);
+    draws(&mut busy, RngStream::Ai, 3);
+
+    assert_eq!(draws(&mut quiet, RngStream::Loot, 8), draws(&mut busy, RngStream::Loot, 8));
+    assert_eq!(
+        draws(&mut quiet, RngStream::Mapgen, 8),
+        draws(&mut busy, RngStream::Mapgen, 8)
+    );
+    assert_ne!(
+        draws(&mut GameRng::new(7), RngStream::Combat, 8),
+        draws(&mut GameRng::new(7), RngStream::Loot, 8)
+    );
+}
+
+#[test]
+fn state_resumes_exactly() {
+    let mut rng = GameRng::new(42);
+    draws(&mut rng, RngStream::Combat, 5);
+    draws(&mut rng, RngStream::Loot, 2);
+
+    let mut resumed = GameRng::from_state(&rng.state());
+    assert_eq!(resumed.seed(), 42);
+    for stream in RngStream::ALL {
+        assert_eq!(draws(&mut resumed, stream, 6), draws(&mut rng, stream, 6), "{stream:?}");
+    }
+}
+
+#[test]
+fn seed_from_command_line() {
+    let args = LaunchArgs::parse(["--seed", "123"]).unwrap();
+    assert_eq!(args.seed, Some(123));
+    assert_eq!(
+        LaunchArgs::parse(["--seed", "-1"]).unwrap_err(),
+        "--seed 应为非负整数，得到 \"-1\""
+    );
+}
@@ -0,0 +1,99 @@
+//! CSV 物品表：表头映射、逐格报错（行:列）与导出后再导入的往返
+
+use bevy_demo::data::csv::{parse_items, write_items};
+use bevy_demo::data::loader;
+use bevy_demo::data::schema::{ArmorSlot, DataManifest, ItemKind};
+use bevy_demo::data::validate::SourceSpan;
+
+#[test]
+fn maps_columns_by_header() {
+    // 列顺序任意、表头忽略大小写；BOM / CRLF / 引号内逗号都能处理
+    let src = "\u{feff}Item_Type,ID,Name,ATK,heal,slot\r\n\
+               weapon,sword,\"剑, 铁制\",10,,\r\n\
+               potion,potion,药水,,25,\r\n\
+               armor,helm,头盔,,,Head\r\n\
+               \r\n\
+               misc,rock,\"\"\"石头\"\"\",,,\r\n";
+    let list = parse_items(src, "items.csv").unwrap();
+
+    let kinds: Vec<_> = list.items.iter().map(|e| (e.id.as_str(), &e.kind)).collect();
+    assert_eq!(
+        kinds,
+        [
+            ("sword", &ItemKind::Weapon { hands: 1 }),
+            ("potion", &ItemKind::Potion { heal: 25 }),
+            ("helm", &ItemKind::Armor { slot: ArmorSlot::Head }),
+            ("rock", &ItemKind::Misc),
+        ]
+    );
+    assert_eq!(list.items[0].name, "剑, 铁制");
+    assert_eq!(list.items[0].atk, 10);
+    assert_eq!(list.items[3].name, "\"石头\"");
+    // 位置指向 id 所在的行与列，空行照样计数
+    assert_eq!(list.spans[3], SourceSpan { line: 6, column: 2 });
+}
+
+#[test]
+fn reports_every_bad_cell_with_row_and_column() {
+    let src = "id,name,atk,heal,item_type,slot\n\
+               sword,剑,ten,,weapon,\n\
+               potion,药水,,,potion,\n\
+               helm,头盔,,,armor,feet\n\
+               wand,法杖,,5,staff,\n\
+               ring,戒指,,3,armor,accessory\n";
+    let report = parse_items(src, "balance.csv").unwrap_err();
+
+    let found: Vec<_> = report
+        .issues
+        .iter()
+        .map(|i| (i.item_id.as_str(), i.span.unwrap().to_string(), i.message.as_str()))
+        .collect();
+    assert_eq!(
+        found,
+        [
+            ("sword", "2:3".into(), "atk 应为整数，得到 \"ten\""),
+            ("potion", "3:5".into(), "potion 需要填写 heal"),
+            ("helm", "4:6".into(), "未知的 slot \"feet\"（可选 head/body/accessory）"),
+            (
+                "wand",
+                "5:5".into(),
+                "未知的 item_type \"staff\"（可选 weapon/armor/potion/scroll/key/misc）"
+            ),
+            ("ring", "6:4".into(), "heal 只适用于 potion"),
+        ]
+    );
+    assert!(report.to_string().starts_with("balance.csv: 5 个问题"));
+}
+
+#[test]
+fn rejects_unknown_or_missing_columns() {
+    let report = parse_items("id,name,colour\nsword,剑,red\n", "bad.csv").unwrap_err();
+    let messages: Vec<_> = report.issues.iter().map(|i| i.message.as_str()).collect();
+    assert_eq!(messages.len(), 2);
+    assert!(messages[0].starts_with("未知列 \"colour\""), "{messages:?}");
+    assert_eq!(messages[1], "缺少列 \"item_type\"");
+
+    let report = parse_items("id,name,item_type\n\"sword,剑,weapon\n", "bad.csv").unwrap_err();
+    assert_eq!(report.issues[0].message, "引号未闭合");
+    assert_eq!(report.issues[0].span, Some(SourceSpan { line: 2, column: 1 }));
+}
+
+#[test]
+fn export_round_trips_shipped_items() {
+    let assets = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("assets");
+    let manifest: DataManifest = bevy::asset::ron::de::from_str(
+        &std::fs::read_to_string(assets.join("data/manifest.ron")).unwrap(),
+    )
+    .unwrap();
+
+    let mut items = Vec::new();
+    for path in &manifest.items {
+        let src = std::fs::read_to_string(assets.join(path)).unwrap();
+        items.extend(loader::parse_items(&src, path).unwrap().items);
+    }
+
+    let csv = write_items(&items);
+    assert!(csv.starts_with("id,name,atk,def,max_hp,rng,heal,item_type,hands,slot,effect,door_id\n"));
+    let parsed = parse_items(&csv, "export.csv").unwrap();
+    assert_eq!(parsed.items, items);
+}
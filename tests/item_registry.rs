@@ -0,0 +1,107 @@
+//! 物品注册表：索引查找、搜索与热重载时句柄稳定
+
+use bevy_demo::data::loader::parse_items;
+use bevy_demo::data::registry::{ItemRegistry, uuid_from_id};
+use bevy_demo::data::schema::ItemList;
+
+fn list(src: &str) -> ItemList {
+    parse_items(src, "test.ron").expect("valid item list")
+}
+
+fn registry() -> ItemRegistry {
+    ItemRegistry::from_list(&list(
+        r#"(items: [
+            (id: "sword_iron", name: "铁剑", atk: 10, kind: Weapon()),
+            (id: "sword_bronze", name: "铜剑", atk: 6, kind: Weapon()),
+            (id: "shield_oak", name: "Oak Shield", def: 2, kind: Armor(slot: Body)),
+        ])"#,
+    ))
+}
+
+#[test]
+fn lookup_by_id_uuid_and_name() {
+    let registry = registry();
+    let sword = registry.by_id("sword_iron").unwrap();
+
+    assert_eq!(registry.get(sword).name, "铁剑");
+    assert_eq!(registry.lookup("SWORD_IRON"), Some(sword));
+    assert_eq!(registry.lookup("铁剑"), Some(sword));
+    assert_eq!(registry.lookup(&uuid_from_id("sword_iron").to_string()), Some(sword));
+    assert_eq!(registry.uuid(sword), uuid_from_id("sword_iron"));
+    assert_eq!(registry.lookup("oak shield"), registry.by_id("shield_oak"));
+    assert_eq!(registry.lookup("sword"), None);
+}
+
+#[test]
+fn search_ranks_prefix_before_fuzzy() {
+    let registry = registry();
+    let ids = |query: &str| -> Vec<String> {
+        registry
+            .search(query)
+            .into_iter()
+            .map(|item| registry.get(item).id.clone())
+            .collect()
+    };
+
+    assert_eq!(ids("sword"), ["sword_iron", "sword_bronze"]);
+    assert_eq!(ids("oak"), ["shield_oak"]);
+    assert_eq!(ids("swbr"), ["sword_bronze"]);
+    assert_eq!(ids("铜"), ["sword_bronze"]);
+    assert!(ids("axe").is_empty());
+}
+
+#[test]
+fn reload_keeps_handles_stable() {
+    let mut registry = registry();
+    let sword = registry.by_id("sword_iron").unwrap();
+    let shield = registry.by_id("shield_oak").unwrap();
+
+    let changed = registry.reload(&list(
+        r#"(items: [
+            (id: "axe", name: "斧", atk: 8, kind: Weapon()),
+            (id: "sword_iron", name: "铁剑", atk: 12, kind: Weapon()),
+            (id: "sword_bronze", name: "铜剑", atk: 6, kind: Weapon()),
+        ])"#,
+    ));
+
+    assert_eq!(changed, [sword]);
+    assert_eq!(registry.by_id("sword_iron"), Some(sword));
+    assert_eq!(registry.get(sword).atk, 12);
+    // 删除的物品不再能查到，但已持有的句柄仍指向旧定义
+    assert_eq!(registry.by_id("shield_oak"), None);
+    assert_eq!(registry.get(shield).name, "Oak Shield");
+    assert!(registry.by_id("axe").is_some());
+    assert_eq!(registry.len(), 3);
+}
+
+#[test]
+fn reload_revives_removed_items_and_keeps_name_owner() {
+    let mut registry = registry();
+    let bronze = registry.by_id("sword_bronze").unwrap();
+    let shield = registry.by_id("shield_oak").unwrap();
+
+    // 删除 shield_oak，同时前面的物品改用它的名字：删除不能带走新主人的名称索引
+    registry.reload(&list(
+        r#"(items: [
+            (id: "sword_iron", name: "铁剑", atk: 10, kind: Weapon()),
+            (id: "sword_bronze", name: "Oak Shield", atk: 6, kind: Weapon()),
+        ])"#,
+    ));
+    assert_eq!(registry.by_id("shield_oak"), None);
+    assert_eq!(registry.by_name("oak shield"), Some(bronze));
+
+    // 重新加入后复用原句柄，已持有的物品与新给的物品仍能堆叠
+    let changed = registry.reload(&list(
+        r#"(items: [
+            (id: "sword_iron", name: "铁剑", atk: 10, kind: Weapon()),
+            (id: "sword_bronze", name: "铜剑", atk: 6, kind: Weapon()),
+            (id: "shield_oak", name: "Oak Shield", def: 3, kind: Armor(slot: Body)),
+        ])"#,
+    ));
+    assert_eq!(changed, [bronze, shield]);
+    assert_eq!(registry.by_id("shield_oak"), Some(shield));
+    assert_eq!(registry.by_uuid(&uuid_from_id("shield_oak")), Some(shield));
+    assert_eq!(registry.by_name("Oak Shield"), Some(shield));
+    assert_eq!(registry.get(shield).def, 3);
+    assert_eq!(registry.len(), 3);
+}
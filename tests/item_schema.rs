@@ -0,0 +1,35 @@
+//! 物品表反序列化：类型是带数据的枚举，拼错 / 缺字段在加载时即被拒绝
+
+use bevy::asset::ron;
+use bevy_demo::data::schema::{ArmorSlot, ItemKind, ItemList};
+
+fn parse(src: &str) -> Result<ItemList, ron::error::SpannedError> {
+    ron::de::from_str(src)
+}
+
+#[test]
+fn shipped_items_parse() {
+    let src = std::fs::read_to_string(
+        std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("assets/data/items.ron"),
+    )
+    .unwrap();
+    let list = parse(&src).expect("assets/data/items.ron must be valid");
+
+    let kind = |id: &str| &list.items.iter().find(|e| e.id == id).unwrap().kind;
+    assert_eq!(kind("sword_iron"), &ItemKind::Weapon { hands: 1 });
+    assert_eq!(kind("armor_leather"), &ItemKind::Armor { slot: ArmorSlot::Body });
+    assert_eq!(kind("potion_heal_small"), &ItemKind::Potion { heal: 25 });
+}
+
+#[test]
+fn invalid_kinds_are_rejected() {
+    for (src, reason) in [
+        (r#"(items: [(id: "p", name: "P", kind: Potoin(heal: 5))])"#, "misspelled kind"),
+        (r#"(items: [(id: "p", name: "P", kind: Potion())])"#, "missing payload"),
+        (r#"(items: [(id: "a", name: "A", kind: Armor(slot: Feet))])"#, "unknown slot"),
+        (r#"(items: [(id: "p", name: "P", heal: 5, kind: Misc)])"#, "legacy field"),
+        (r#"(items: [(id: "p", name: "P")])"#, "missing kind"),
+    ] {
+        assert!(parse(src).is_err(), "{reason} should be rejected: {src}");
+    }
+}
@@ -0,0 +1,146 @@
+//! 物品表校验：收集全部问题，带物品 id 与源文件位置
+
+use bevy::asset::ron;
+use bevy_demo::data::loader::parse_items;
+use bevy_demo::data::merge::merge_item_lists;
+use bevy_demo::data::schema::DataManifest;
+use bevy_demo::data::validate::{SourceSpan, validate};
+
+const BROKEN: &str = r#"(
+  items: [
+    (id: "potion", name: "药水", kind: Potion(heal: 10)),
+    (id: "Potion", name: "大药水", kind: Potion(heal: 30)),
+    (id: "nameless", name: " ", kind: Misc),
+    // 负数治疗
+    (id: "poison", name: "毒药", kind: Potion(heal: -5)),
+    (id: "lockpick", name: "撬锁器", kind: Key(door_id: "")),
+  ],
+)"#;
+
+#[test]
+fn shipped_items_are_valid() {
+    let assets = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("assets");
+    let manifest: DataManifest =
+        ron::de::from_str(&std::fs::read_to_string(assets.join("data/manifest.ron")).unwrap())
+            .unwrap();
+    assert!(!manifest.items.is_empty());
+
+    for path in &manifest.items {
+        let src = std::fs::read_to_string(assets.join(path)).unwrap();
+        let list = parse_items(&src, path).unwrap();
+        let report = validate(&list);
+        assert!(report.is_ok(), "{report}");
+    }
+}
+
+/// 名称也能用来 give，重名（忽略大小写）无论在同一文件还是不同文件都报告
+#[test]
+fn duplicate_names_are_reported() {
+    let base = parse_items(
+        r#"(items: [
+            (id: "shield_oak", name: "Oak Shield", def: 2, kind: Armor(slot: Body)),
+            (id: "shield_pine", name: "oak shield", def: 1, kind: Armor(slot: Body)),
+        ])"#,
+        "base.ron",
+    )
+    .unwrap();
+    let report = validate(&base);
+    assert_eq!(report.issues.len(), 1);
+    assert_eq!(report.issues[0].item_id, "shield_pine");
+    assert_eq!(report.issues[0].message, "物品名称 \"oak shield\" 与 shield_oak 重复");
+
+    // 各文件单独没有问题，合并后重名
+    let pack = parse_items(
+        r#"(items: [(id: "buckler", name: "OAK SHIELD", def: 1, kind: Armor(slot: Body))])"#,
+        "pack.ron",
+    )
+    .unwrap();
+    let first = parse_items(
+        r#"(items: [(id: "shield_oak", name: "Oak Shield", def: 2, kind: Armor(slot: Body))])"#,
+        "base.ron",
+    )
+    .unwrap();
+    assert!(validate(&pack).is_ok());
+    let merged = merge_item_lists("manifest", &[&first, &pack]);
+    let report = validate(&merged.list);
+    assert_eq!(report.issues.len(), 1);
+    assert_eq!(report.issues[0].message, "物品名称 \"OAK SHIELD\" 与 shield_oak 重复");
+
+    // 同 id 覆盖不算重名
+    let renamed = parse_items(
+        r#"(items: [(id: "shield_oak", name: "Oak Shield", def: 3, kind: Armor(slot: Body))])"#,
+        "pack.ron",
+    )
+    .unwrap();
+    assert!(validate(&merge_item_lists("manifest", &[&first, &renamed]).list).is_ok());
+}
+
+#[test]
+fn collects_every_issue_with_id_and_span() {
+    let list = parse_items(BROKEN, "broken.ron").unwrap();
+    let report = validate(&list);
+
+    let found: Vec<_> = report
+        .issues
+        .iter()
+        .map(|issue| (issue.item_id.as_str(), issue.span))
+        .collect();
+    let at = |line, column| Some(SourceSpan { line, column });
+    assert_eq!(
+        found,
+        [
+            ("Potion", at(4, 6)),
+            ("nameless", at(5, 6)),
+            ("poison", at(7, 6)),
+            ("lockpick", at(8, 6)),
+        ]
+    );
+
+    let text = report.to_string();
+    assert!(text.starts_with("broken.ron: 4 个问题"), "{text}");
+    assert!(text.contains("重复的物品 id（首次出现于 3:6）"), "{text}");
+    assert!(text.contains("heal 不能为负数: -5"), "{text}");
+}
+
+/// JSON / TOML 与 RON 走同一套校验，报告同样带位置
+#[test]
+fn json_and_toml_report_like_ron() {
+    let json = r#"{
+  "items": [
+    {"id": "potion", "name": "药水", "kind": {"Potion": {"heal": 10}}},
+    {"id": "Potion", "name": "大药水", "kind": {"Potion": {"heal": 30}}},
+    {"id": "lockpick", "name": "撬锁器", "kind": {"Key": {"door_id": ""}}}
+  ]
+}"#;
+    let toml = r#"# 手工编辑
+[[items]]
+id = "potion"
+name = "药水"
+kind = { Potion = { heal = 10 } }
+
+[[items]]
+id = "Potion"
+name = "大药水"
+kind = { Potion = { heal = 30 } }
+
+[[items]]
+id = "lockpick"
+name = "撬锁器"
+kind = { Key = { door_id = "" } }
+"#;
+
+    let at = |line, column| Some(SourceSpan { line, column });
+    for (src, source, spans) in [
+        (json, "items.json", [at(4, 7), at(5, 7)]),
+        (toml, "items.toml", [at(8, 1), at(13, 1)]),
+    ] {
+        let report = validate(&parse_items(src, source).unwrap());
+        let found: Vec<_> = report
+            .issues
+            .iter()
+            .map(|issue| (issue.item_id.as_str(), issue.span))
+            .collect();
+        assert_eq!(found, [("Potion", spans[0]), ("lockpick", spans[1])], "{source}");
+        assert!(report.to_string().starts_with(&format!("{source}: 2 个问题")));
+    }
+}
@@ -0,0 +1,96 @@
+//! 成长曲线：经验公式 / 经验表、等级上限、特定等级的成长与校验
+
+use bevy_demo::character::components::{BaseStats, Stats};
+use bevy_demo::data::loader::parse_items;
+use bevy_demo::data::progression::{ExpCurve, Progression};
+use bevy_demo::data::registry::ItemRegistry;
+
+fn progression(src: &str) -> Progression {
+    bevy::asset::ron::de::from_str(src).expect("valid progression")
+}
+
+fn registry() -> ItemRegistry {
+    ItemRegistry::from_list(
+        &parse_items(
+            r#"(items: [(id: "potion", name: "药水", kind: Potion(heal: 10))])"#,
+            "test.ron",
+        )
+        .unwrap(),
+    )
+}
+
+#[test]
+fn default_matches_builtin_curve() {
+    let progression = Progression::default();
+    assert_eq!(progression.exp_to_next(1), Some(10));
+    assert_eq!(progression.exp_to_next(3), Some(90));
+
+    // 升级只推进等级与经验，属性成长由修正管线按等级换算
+    let mut base = BaseStats::from(&Stats::default());
+    base.gain_exp(100, &progression);
+    assert_eq!((base.lv, base.exp, base.max_hp, base.atk, base.def), (3, 50, 20, 2, 1));
+    let total = progression.total_growth(base.lv);
+    assert_eq!((total.max_hp, total.atk, total.def), (4, 2, 2));
+}
+
+#[test]
+fn table_repeats_last_entry_and_stops_at_cap() {
+    let progression = progression("(level_cap: 4, exp: Table([5, 8]), growth: (atk: 1))");
+    assert_eq!(progression.exp, ExpCurve::Table(vec![5, 8]));
+    assert_eq!(progression.exp_to_next(1), Some(5));
+    assert_eq!(progression.exp_to_next(3), Some(8));
+    assert_eq!(progression.exp_to_next(4), None);
+
+    let mut base = BaseStats::from(&Stats::default());
+    base.gain_exp(100, &progression);
+    assert_eq!((base.lv, base.exp), (4, 79));
+    assert_eq!(progression.total_growth(base.lv).atk, 3);
+    let stats = Stats { lv: base.lv, exp: base.exp, ..Stats::default() };
+    assert_eq!(stats.exp_label(&progression), "79/满级");
+}
+
+#[test]
+fn reward_growth_replaces_default_growth() {
+    let progression = progression(
+        r#"(
+            level_cap: 10,
+            exp: Formula(base: 10, exponent: 1),
+            growth: (max_hp: 2, atk: 1),
+            rewards: [(level: 3, growth: (max_hp: 10), items: [("potion", 2)], skill_points: 1)],
+        )"#,
+    );
+    assert_eq!(progression.growth_at(2).max_hp, 2);
+    assert_eq!(progression.growth_at(3).max_hp, 10);
+    assert_eq!(progression.growth_at(3).atk, 0);
+
+    let total = progression.total_growth(4);
+    assert_eq!((total.max_hp, total.atk), (14, 2));
+    assert_eq!(progression.growth_between(2, 4).describe(), "生命值 +12，攻击力 +1");
+    assert!(progression.validate(&registry()).is_empty());
+}
+
+#[test]
+fn validate_reports_every_problem() {
+    let progression = progression(
+        r#"(
+            level_cap: 5,
+            exp: Table([10, 0]),
+            growth: (),
+            rewards: [
+                (level: 1, skill_points: 1),
+                (level: 3, items: [("elixir", 1), ("potion", 0)]),
+                (level: 3),
+            ],
+        )"#,
+    );
+    assert_eq!(
+        progression.validate(&registry()),
+        [
+            "exp 的经验表第 2 项应为正数，得到 0",
+            "rewards 的等级 1 超出范围 2..=5",
+            "等级 3 的奖励物品 elixir 不存在",
+            "等级 3 的奖励物品 potion 数量为 0",
+            "rewards 的等级 3 重复",
+        ]
+    );
+}
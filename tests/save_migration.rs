@@ -0,0 +1,172 @@
+//! 存档迁移：`tests/fixtures/saves/v<N>.json` 是每个历史版本的样例存档，
+//! 都必须能升级到当前版本并读成同一个状态
+
+use bevy_demo::data::loader::parse_items;
+use bevy_demo::data::registry::ItemRegistry;
+use bevy_demo::save::migrate::{MIGRATIONS, migrate};
+use bevy_demo::save::schema::{SAVE_VERSION, SaveFile};
+use bevy_demo::save::tool;
+use std::path::PathBuf;
+
+fn fixture(version: u32) -> PathBuf {
+    PathBuf::from(format!("tests/fixtures/saves/v{version}.json"))
+}
+
+fn read_fixture(version: u32) -> String {
+    std::fs::read_to_string(fixture(version)).expect("every save version has a fixture")
+}
+
+/// 去掉 `version` 之后才新增的字段，用于与旧版本样例比较
+fn as_of(version: u32, mut save: SaveFile) -> SaveFile {
+    if version < 3 {
+        save.rng = None;
+    }
+    if version < 4 {
+        for actor in &mut save.actors {
+            actor.effects.clear();
+        }
+    }
+    if version < 5 {
+        for actor in &mut save.actors {
+            actor.skill_points = 0;
+        }
+    }
+    if version < 6 {
+        for actor in &mut save.actors {
+            actor.class = None;
+        }
+    }
+    if version < 8 {
+        for actor in &mut save.actors {
+            actor.modifiers.clear();
+        }
+    }
+    save
+}
+
+#[test]
+fn migrations_cover_every_version() {
+    let from: Vec<u32> = MIGRATIONS.iter().map(|m| m.from).collect();
+    assert_eq!(from, (1..SAVE_VERSION).collect::<Vec<_>>());
+}
+
+#[test]
+fn every_fixture_migrates_to_current() {
+    let (current, migrated) = SaveFile::from_json(&read_fixture(SAVE_VERSION)).unwrap();
+    assert!(migrated.steps.is_empty());
+
+    for version in 1..SAVE_VERSION {
+        let (save, migrated) = SaveFile::from_json(&read_fixture(version)).unwrap();
+        assert_eq!(migrated.from, version);
+        assert_eq!(migrated.steps.len(), (SAVE_VERSION - version) as usize);
+        assert_eq!(
+            save,
+            as_of(version, current.clone()),
+            "v{version} 升级后应与 v{SAVE_VERSION} 样例一致"
+        );
+    }
+}
+
+#[test]
+fn v1_keeps_level_and_base_attributes() {
+    let (save, _) = SaveFile::from_json(&read_fixture(1)).unwrap();
+    let player = &save.actors[0];
+    assert_eq!((player.level, player.exp, player.hp), (3, 50, 15));
+    assert_eq!((player.base.atk, player.equipment.weapon.as_deref()), (2, Some("sword_iron")));
+    // 派生属性不来自存档：读档时只带基础值，等待重算
+    let stats = player.stats();
+    assert_eq!((stats.lv, stats.hp, stats.atk, stats.max_hp), (3, 15, 2, 0));
+    assert_eq!(player.base_stats().lv, 3);
+}
+
+#[test]
+fn unsupported_versions_are_rejected() {
+    let mut newer = serde_json::json!({ "version": SAVE_VERSION + 1, "actors": [] });
+    assert_eq!(
+        migrate(&mut newer).unwrap_err(),
+        format!("存档版本 {} 比游戏新（当前版本 {SAVE_VERSION}）", SAVE_VERSION + 1)
+    );
+
+    let mut unversioned = serde_json::json!({ "actors": [] });
+    assert_eq!(migrate(&mut unversioned).unwrap_err(), "缺少 version 字段");
+
+    let mut broken = serde_json::json!({ "version": 1, "actors": [{ "faction": "player" }] });
+    assert_eq!(
+        migrate(&mut broken).unwrap_err(),
+        "v1 → v2 失败: actors[0].stats 缺失"
+    );
+}
+
+#[test]
+fn tool_migrates_in_place_with_backup() {
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("save_migration");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("old.json");
+    std::fs::copy(fixture(1), &path).unwrap();
+
+    let report = tool::migrate(&path, None).unwrap();
+    assert!(report.ends_with(&format!("v1 → v{SAVE_VERSION} 已写入 {}", path.display())));
+    assert_eq!(
+        std::fs::read_to_string(dir.join("old.json.bak")).unwrap(),
+        read_fixture(1)
+    );
+
+    let (upgraded, migrated) =
+        SaveFile::from_json(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    assert!(migrated.steps.is_empty());
+    assert_eq!(
+        upgraded,
+        as_of(1, SaveFile::from_json(&read_fixture(SAVE_VERSION)).unwrap().0)
+    );
+
+    let report = tool::migrate(&path, None).unwrap();
+    assert!(report.ends_with("无需升级"), "{report}");
+}
+
+#[test]
+fn tool_migrates_to_output_file() {
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("save_migration_out");
+    let _ = std::fs::remove_dir_all(&dir);
+    let out = dir.join("upgraded").join("out.json");
+
+    tool::migrate(&fixture(1), Some(&out)).unwrap();
+    let (upgraded, migrated) =
+        SaveFile::from_json(&std::fs::read_to_string(&out).unwrap()).unwrap();
+    assert!(migrated.steps.is_empty());
+    assert_eq!(upgraded.version, SAVE_VERSION);
+    // 与游戏内存档相同，经临时文件改名写出，不留下 .tmp
+    assert!(!dir.join("upgraded").join("out.json.tmp").exists());
+}
+
+#[test]
+fn tool_inspect_lists_actors() {
+    let report = tool::inspect(&fixture(1)).unwrap();
+    assert!(report.contains(&format!("存档版本 v1，读取时升级到 v{SAVE_VERSION}")), "{report}");
+    assert!(report.contains("玩家 [Player] Lv3 EXP 50 HP 15 基础 MAX_HP 20 ATK 2 DEF 1 RNG 1"), "{report}");
+    assert!(report.contains("装备: weapon=sword_iron"));
+    assert!(report.contains("goblin [Monster] Lv1"));
+}
+
+#[test]
+fn tool_inspect_lists_effects() {
+    let report = tool::inspect(&fixture(SAVE_VERSION)).unwrap();
+    assert!(report.contains("（当前版本）"), "{report}");
+    assert!(report.contains("效果: 回春 ×2（剩余 2 回合）"), "{report}");
+    assert!(report.contains("技能点: 1"), "{report}");
+    assert!(report.contains("职业: adventurer"), "{report}");
+    assert!(report.contains("修正: buff:rage atk +5"), "{report}");
+}
+
+/// 损坏存档的背包容量超过上限时报告问题，而不是按该容量分配格子
+#[test]
+fn oversized_backpack_is_rejected() {
+    let text = std::fs::read_to_string("tests/fixtures/saves/huge_capacity.json").unwrap();
+    let (save, _) = SaveFile::from_json(&text).unwrap();
+    let items = std::fs::read_to_string("assets/data/items.ron").unwrap();
+    let registry = ItemRegistry::from_list(&parse_items(&items, "items.ron").unwrap());
+    assert_eq!(
+        save.validate(&registry),
+        [format!("玩家 背包: 容量 {} 超过上限 1000", usize::MAX)]
+    );
+}
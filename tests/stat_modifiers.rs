@@ -0,0 +1,95 @@
+//! 属性修正管线：求值顺序、来源标签与明细
+
+use bevy_demo::character::modifiers::{
+    ModifierOp, ModifierSource, Stat, StatModifier, StatModifiers, evaluate, level_modifiers,
+};
+use bevy_demo::data::progression::Progression;
+
+fn buff(stat: Stat, op: ModifierOp, name: &str) -> StatModifier {
+    StatModifier::new(stat, op, ModifierSource::Buff(name.into()))
+}
+
+#[test]
+fn stages_apply_in_documented_order() {
+    // 收集顺序故意打乱：阶段顺序不受影响
+    let modifiers = vec![
+        buff(Stat::Atk, ModifierOp::Multiply(200), "rage"),
+        buff(Stat::Atk, ModifierOp::PercentAdd(50), "a"),
+        buff(Stat::Atk, ModifierOp::Flat(8), "b"),
+        buff(Stat::Atk, ModifierOp::PercentAdd(50), "c"),
+    ];
+    // (2 + 8) × (100% + 50% + 50%) × 2
+    assert_eq!(evaluate(Stat::Atk, 2, &modifiers).value, 40);
+    // 其他属性不受影响
+    assert_eq!(evaluate(Stat::Def, 1, &modifiers).value, 1);
+}
+
+#[test]
+fn override_then_clamp() {
+    let modifiers = vec![
+        buff(Stat::Def, ModifierOp::Max(5), "cap"),
+        buff(Stat::Def, ModifierOp::Override(3), "first"),
+        buff(Stat::Def, ModifierOp::Flat(100), "big"),
+        buff(Stat::Def, ModifierOp::Override(9), "last"),
+    ];
+    // 最后一个覆盖生效，再被上限夹到 5
+    assert_eq!(evaluate(Stat::Def, 1, &modifiers).value, 5);
+
+    let floor = vec![
+        buff(Stat::Atk, ModifierOp::Multiply(0), "curse"),
+        buff(Stat::Atk, ModifierOp::Min(1), "floor"),
+    ];
+    assert_eq!(evaluate(Stat::Atk, 10, &floor).value, 1);
+}
+
+#[test]
+fn result_is_rounded_once_at_the_end() {
+    let modifiers = vec![
+        buff(Stat::Atk, ModifierOp::PercentAdd(25), "a"),
+        buff(Stat::Atk, ModifierOp::PercentAdd(25), "b"),
+    ];
+    // 3 × 1.5 = 4.5 → 5（中间不取整）
+    let breakdown = evaluate(Stat::Atk, 3, &modifiers);
+    assert_eq!(breakdown.value, 5);
+    let deltas: Vec<f64> = breakdown.steps.iter().map(|(_, delta)| *delta).collect();
+    assert_eq!(deltas, vec![0.75, 0.75]);
+}
+
+#[test]
+fn breakdown_lists_every_source() {
+    let mut modifiers = level_modifiers(3, &Progression::default());
+    modifiers.push(StatModifier::new(
+        Stat::Atk,
+        ModifierOp::Flat(10),
+        ModifierSource::Equipment {
+            slot: "weapon",
+            item: "粗铁剑".into(),
+        },
+    ));
+    modifiers.push(buff(Stat::Atk, ModifierOp::PercentAdd(50), "rage"));
+
+    let breakdown = evaluate(Stat::Atk, 2, &modifiers);
+    assert_eq!(breakdown.value, 21);
+    assert_eq!(
+        breakdown.describe(),
+        "攻击力 21 = 基础 2\n  +2     等级 Lv3（+2）\n  +10    装备 weapon 粗铁剑（+10）\n  +50%   增益 rage（+7）"
+    );
+}
+
+#[test]
+fn source_tags_round_trip() {
+    for tag in ["buff:rage", "aura:banner"] {
+        let source: ModifierSource = tag.parse().unwrap();
+        assert_eq!(source.to_string(), tag);
+    }
+    assert!("level".parse::<ModifierSource>().is_err());
+    assert!("buff:".parse::<ModifierSource>().is_err());
+
+    let mut modifiers = StatModifiers::default();
+    modifiers.add(buff(Stat::Atk, ModifierOp::Flat(1), "rage"));
+    modifiers.add(buff(Stat::Def, ModifierOp::Flat(1), "rage"));
+    modifiers.add(buff(Stat::Def, ModifierOp::Flat(1), "guard"));
+    assert_eq!(modifiers.remove_source("buff:rage"), 2);
+    assert_eq!(modifiers.remove_source("buff:rage"), 0);
+    assert_eq!(modifiers.0.len(), 1);
+}